@@ -0,0 +1,34 @@
+//! Times every registered day on its input in `inputs/`.
+//!
+//! Run with `cargo bench`; set `AOC_BENCH_ITERATIONS` to change the
+//! number of iterations per day.
+
+use aoc22::bench::{self, DEFAULT_ITERATIONS};
+use aoc22::input::InputSource;
+use aoc22::registry;
+use std::env;
+use std::path::Path;
+
+fn main() {
+    let iterations = env::var("AOC_BENCH_ITERATIONS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_ITERATIONS);
+    let inputs = InputSource::Dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs"));
+
+    let mut timings = Vec::new();
+    for day in registry::days() {
+        let input = match inputs.load(day.number) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("skipping day {}: {}", day.number, err);
+                continue;
+            }
+        };
+        match bench::bench_day(&day, &input, iterations) {
+            Ok(day_timings) => timings.push(day_timings),
+            Err(err) => eprintln!("skipping day {}: {}", day.number, err),
+        }
+    }
+    print!("{}", bench::report(&timings));
+}
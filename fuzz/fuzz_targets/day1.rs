@@ -0,0 +1,14 @@
+//! `cargo +nightly fuzz run day1`: no input may make day 1 panic, whether
+//! it is rejected by `parse` or solved.
+#![no_main]
+
+use aoc22::day1::Day1;
+use aoc22::solution::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Day1::parse(input) {
+        Day1::part1(&parsed);
+        Day1::part2(&parsed);
+    }
+});
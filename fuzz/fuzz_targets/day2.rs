@@ -0,0 +1,14 @@
+//! `cargo +nightly fuzz run day2`: no input may make day 2 panic, whether
+//! it is rejected by `parse` or solved.
+#![no_main]
+
+use aoc22::day2::Day2;
+use aoc22::solution::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Day2::parse(input) {
+        Day2::part1(&parsed);
+        Day2::part2(&parsed);
+    }
+});
@@ -0,0 +1,23 @@
+//! `cargo +nightly fuzz run day2_rules`: no rules file and strategy guide
+//! may make the day 2 `score` command panic, whether they are rejected or
+//! scored. The input is the rules, a NUL byte, then the guide.
+#![no_main]
+
+use aoc22::day2::Rules;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let (rules, guide) = input.split_once('\0').unwrap_or((input, ""));
+    let Ok(rules) = Rules::parse(rules) else {
+        return;
+    };
+    if let Ok(guide) = rules.parse_guide(guide) {
+        guide.analyze();
+        guide.optimal_plays();
+        if let Some(decodings) = guide.decodings() {
+            for (decoding, _) in decodings {
+                rules.describe(&decoding);
+            }
+        }
+    }
+});
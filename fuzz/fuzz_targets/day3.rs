@@ -0,0 +1,14 @@
+//! `cargo +nightly fuzz run day3`: no input may make day 3 panic, whether
+//! it is rejected by `parse` or solved.
+#![no_main]
+
+use aoc22::day3::Day3;
+use aoc22::solution::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Day3::parse(input) {
+        Day3::part1(&parsed);
+        Day3::part2(&parsed);
+    }
+});
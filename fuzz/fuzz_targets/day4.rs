@@ -0,0 +1,14 @@
+//! `cargo +nightly fuzz run day4`: no input may make day 4 panic, whether
+//! it is rejected by `parse` or solved.
+#![no_main]
+
+use aoc22::day4::Day4;
+use aoc22::solution::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Day4::parse(input) {
+        Day4::part1(&parsed);
+        Day4::part2(&parsed);
+    }
+});
@@ -0,0 +1,14 @@
+//! `cargo +nightly fuzz run day5`: no input may make day 5 panic, whether
+//! it is rejected by `parse` or solved.
+#![no_main]
+
+use aoc22::day5::Day5;
+use aoc22::solution::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Day5::parse(input) {
+        Day5::part1(&parsed);
+        Day5::part2(&parsed);
+    }
+});
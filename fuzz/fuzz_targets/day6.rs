@@ -0,0 +1,14 @@
+//! `cargo +nightly fuzz run day6`: no input may make day 6 panic, whether
+//! it is rejected by `parse` or solved.
+#![no_main]
+
+use aoc22::day6::Day6;
+use aoc22::solution::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Day6::parse(input) {
+        Day6::part1(&parsed);
+        Day6::part2(&parsed);
+    }
+});
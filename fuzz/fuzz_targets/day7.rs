@@ -0,0 +1,14 @@
+//! `cargo +nightly fuzz run day7`: no input may make day 7 panic, whether
+//! it is rejected by `parse` or solved.
+#![no_main]
+
+use aoc22::day7::Day7;
+use aoc22::solution::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Day7::parse(input) {
+        Day7::part1(&parsed);
+        Day7::part2(&parsed);
+    }
+});
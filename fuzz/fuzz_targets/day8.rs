@@ -0,0 +1,14 @@
+//! `cargo +nightly fuzz run day8`: no input may make day 8 panic, whether
+//! it is rejected by `parse` or solved.
+#![no_main]
+
+use aoc22::day8::Day8;
+use aoc22::solution::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Day8::parse(input) {
+        Day8::part1(&parsed);
+        Day8::part2(&parsed);
+    }
+});
@@ -0,0 +1,14 @@
+//! `cargo +nightly fuzz run day9`: no input may make day 9 panic, whether
+//! it is rejected by `parse` or solved.
+#![no_main]
+
+use aoc22::day9::Day9;
+use aoc22::solution::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = Day9::parse(input) {
+        Day9::part1(&parsed);
+        Day9::part2(&parsed);
+    }
+});
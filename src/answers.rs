@@ -0,0 +1,126 @@
+use crate::error::ParseError;
+use crate::parser;
+use crate::solution::Part;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_DIR: &str = "answers";
+
+/// The recorded answers for one day, as found in `answers/dayN.toml`:
+///
+/// ```toml
+/// part1 = "70369"
+/// part2 = "203002"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Answers {
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io { path: PathBuf, error: io::Error },
+    Parse { path: PathBuf, error: ParseError },
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswersError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            AnswersError::Parse { path, error } => write!(
+                f,
+                "{}:{}:{}: {}: '{}'",
+                path.display(),
+                error.line,
+                error.column,
+                error.message,
+                error.text
+            ),
+        }
+    }
+}
+
+pub fn day_file(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{}.toml", day))
+}
+
+/// Loads the answers recorded for `day`; a missing file means none are.
+pub fn load(dir: &Path, day: u8) -> Result<Answers, AnswersError> {
+    load_file(day, day_file(dir, day))
+}
+
+/// Loads an answers file for `day` by path; a missing file means no answers
+/// are known.
+pub fn load_file(day: u8, path: PathBuf) -> Result<Answers, AnswersError> {
+    match fs::read_to_string(&path) {
+        Ok(text) => parse(day, &text).map_err(|error| AnswersError::Parse { path, error }),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+        Err(error) => Err(AnswersError::Io { path, error }),
+    }
+}
+
+fn parse_value(value: &str) -> Option<String> {
+    if let Some(quoted) = value.strip_prefix('"') {
+        let unquoted = quoted.strip_suffix('"')?;
+        if unquoted.contains('"') {
+            return None;
+        }
+        Some(unquoted.to_owned())
+    } else if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) {
+        Some(value.to_owned())
+    } else {
+        None
+    }
+}
+
+/// Parses the small TOML subset used by answer files: `part1`/`part2` keys
+/// with string or integer values, blank lines and `#` comments.
+pub fn parse(day: u8, text: &str) -> Result<Answers, ParseError> {
+    let mut answers = Answers::default();
+    for line in parser::lines(day, text) {
+        let trimmed = line.text.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.key_value("=", "expected 'key = value'")?;
+        let value = parse_value(value).ok_or_else(|| line.error(value, "invalid value"))?;
+        match key {
+            "part1" => answers.part1 = Some(value),
+            "part2" => answers.part2 = Some(value),
+            _ => return Err(line.error(key, "unknown key")),
+        }
+    }
+    Ok(answers)
+}
+
+#[test]
+fn test_parse() {
+    let answers = parse(5, "# day 5\npart1 = \"CMZ\"\n\npart2 = 12\n").unwrap();
+    assert_eq!(Some("CMZ"), answers.get(Part::One));
+    assert_eq!(Some("12"), answers.get(Part::Two));
+
+    assert_eq!(Answers::default(), parse(5, "").unwrap());
+    let err = parse(5, "part3 = 1").unwrap_err();
+    assert_eq!(
+        (5, 1, 1, "part3"),
+        (err.day, err.line, err.column, err.text.as_str())
+    );
+    let err = parse(5, "part1 = 1\npart2 = \"x").unwrap_err();
+    assert_eq!(
+        (2, 9, "invalid value"),
+        (err.line, err.column, err.message.as_str())
+    );
+    assert_eq!(1, parse(5, "part1").unwrap_err().column);
+}
@@ -0,0 +1,142 @@
+use crate::error::ParseError;
+use crate::solution::{Day, Part};
+use std::fmt::Write;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_ITERATIONS: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarizes at least one sample.
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        Stats {
+            min: sorted[0],
+            median: sorted[sorted.len() / 2],
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DayTimings {
+    pub day: u8,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl DayTimings {
+    /// Median time to parse the input and solve both parts.
+    pub fn total(&self) -> Duration {
+        self.parse.median + self.part1.median + self.part2.median
+    }
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = black_box(f());
+    (result, start.elapsed())
+}
+
+/// Parses `input` and solves both parts `iterations` times, timing each stage.
+pub fn bench_day(day: &Day, input: &str, iterations: usize) -> Result<DayTimings, ParseError> {
+    let iterations = iterations.max(1);
+    let mut parse = Vec::with_capacity(iterations);
+    let mut part1 = Vec::with_capacity(iterations);
+    let mut part2 = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let (parsed, elapsed) = time(|| day.parse(black_box(input)));
+        let parsed = parsed?;
+        parse.push(elapsed);
+        part1.push(time(|| parsed.solve(Part::One)).1);
+        part2.push(time(|| parsed.solve(Part::Two)).1);
+    }
+    Ok(DayTimings {
+        day: day.number,
+        parse: Stats::from_samples(&parse),
+        part1: Stats::from_samples(&part1),
+        part2: Stats::from_samples(&part2),
+    })
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.3}s", nanos as f64 / 1e9)
+    }
+}
+
+/// Renders the timings as a table followed by the total of the medians.
+pub fn report(timings: &[DayTimings]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:<5} {:<6} {:>10} {:>10} {:>10}",
+        "day", "stage", "min", "median", "max"
+    );
+    for day in timings {
+        for (stage, stats) in [
+            ("parse", day.parse),
+            ("part1", day.part1),
+            ("part2", day.part2),
+        ] {
+            let _ = writeln!(
+                out,
+                "{:<5} {:<6} {:>10} {:>10} {:>10}",
+                day.day,
+                stage,
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.max)
+            );
+        }
+    }
+    let total = timings.iter().map(DayTimings::total).sum::<Duration>();
+    let _ = writeln!(
+        out,
+        "total (median) for {} days: {}",
+        timings.len(),
+        format_duration(total)
+    );
+    out
+}
+
+#[test]
+fn test_stats() {
+    let ms = Duration::from_millis;
+    let stats = Stats::from_samples(&[ms(5), ms(1), ms(9), ms(3), ms(4)]);
+    assert_eq!((ms(1), ms(4), ms(9)), (stats.min, stats.median, stats.max));
+}
+
+#[test]
+fn test_format_duration() {
+    assert_eq!("999ns", format_duration(Duration::from_nanos(999)));
+    assert_eq!("1.5µs", format_duration(Duration::from_nanos(1_500)));
+    assert_eq!("2.25ms", format_duration(Duration::from_micros(2_250)));
+    assert_eq!("1.000s", format_duration(Duration::from_secs(1)));
+}
+
+#[test]
+fn test_bench_day() {
+    let day = crate::registry::find(6).unwrap();
+    let timings = bench_day(&day, "mjqjpqmgbljsphdztnvjfqwrcgsmlb", 3).unwrap();
+    assert_eq!(6, timings.day);
+    assert!(timings.parse.min <= timings.parse.max);
+    assert!(report(&[timings]).contains("total (median) for 1 days"));
+    assert!(bench_day(&day, "NOT VALID", 3).is_err());
+}
@@ -1,47 +1,322 @@
+use crate::error::ParseError;
+use crate::parser::{self, Line};
+use crate::solution::Solution;
+use crate::stats::{self, Summary};
+#[cfg(test)]
+use proptest::prelude::*;
+use std::cmp::Reverse;
 use std::collections::BinaryHeap;
-use std::str::FromStr;
+use std::fmt;
+use std::io::{self, BufRead};
 
-pub fn solve() -> u32 {
-    let input = include_str!("../inputs/day1.txt");
+/// An elf and the calories it carries. Elves are numbered from 0 in the
+/// order they appear.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Elf {
+    pub index: usize,
+    pub total: u64,
+}
 
-    let mut max_elf = 0u32;
-    let mut current_elf = 0u32;
+/// Groups lines of calories into elves; blank lines separate elves, and any
+/// number of them counts as one separator.
+#[derive(Default)]
+struct Grouping {
+    index: usize,
+    total: Option<u64>,
+}
 
-    for line in input.lines() {
-        let line = line.trim();
-        if line.len() == 0 {
-            if current_elf > max_elf {
-                max_elf = current_elf;
-            }
-            current_elf = 0;
-        } else {
-            current_elf += u32::from_str(line).unwrap();
+impl Grouping {
+    /// Adds one line; returns the elf that a blank line ends.
+    fn add(&mut self, line: Line) -> Result<Option<Elf>, ParseError> {
+        let text = line.text.trim();
+        if text.is_empty() {
+            return Ok(self.finish());
         }
+        let calories: u64 = line.int(text, "expected calories")?;
+        let total = self
+            .total
+            .unwrap_or(0)
+            .checked_add(calories)
+            .ok_or_else(|| line.error(text, "the elf carries too many calories"))?;
+        self.total = Some(total);
+        Ok(None)
     }
 
-    max_elf
+    /// Ends the current elf, if it carries anything.
+    fn finish(&mut self) -> Option<Elf> {
+        let total = self.total.take()?;
+        let elf = Elf {
+            index: self.index,
+            total,
+        };
+        self.index += 1;
+        Some(elf)
+    }
 }
 
-pub fn solve2() -> u32 {
-    let input = include_str!("../inputs/day1.txt");
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse(ParseError),
+}
 
-    let mut heap : BinaryHeap<u32> = BinaryHeap::new();
-    let mut current_elf = 0u32;
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(err) => err.fmt(f),
+            ReadError::Parse(err) => err.fmt(f),
+        }
+    }
+}
 
-    for line in input.lines() {
-        let line = line.trim();
-        if line.len() == 0 {
-            heap.push(current_elf);
-            current_elf = 0;
-        } else {
-            current_elf += u32::from_str(line).unwrap();
+/// The elves in a reader, read one line at a time. Iteration stops after
+/// the first error.
+pub struct Elves<R> {
+    reader: R,
+    line: String,
+    number: usize,
+    grouping: Grouping,
+    done: bool,
+}
+
+pub fn elves<R: BufRead>(reader: R) -> Elves<R> {
+    Elves {
+        reader,
+        line: String::new(),
+        number: 0,
+        grouping: Grouping::default(),
+        done: false,
+    }
+}
+
+impl<R: BufRead> Iterator for Elves<R> {
+    type Item = Result<Elf, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => {
+                    self.done = true;
+                    return self.grouping.finish().map(Ok);
+                }
+                Ok(_) => {
+                    self.number += 1;
+                    let text = self.line.trim_end_matches(['\n', '\r']);
+                    match self.grouping.add(Line::new(Day1::DAY, self.number, text)) {
+                        Ok(Some(elf)) => return Some(Ok(elf)),
+                        Ok(None) => {}
+                        Err(err) => {
+                            self.done = true;
+                            return Some(Err(ReadError::Parse(err)));
+                        }
+                    }
+                }
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(ReadError::Io(err)));
+                }
+            }
         }
+        None
     }
+}
 
-    let mut top_elfs_load = 0;
-    for _ in 1..=3 {
-        top_elfs_load += heap.pop().unwrap()
+/// The `n` elves carrying the most calories among those pushed, held in a
+/// min-heap of at most `n` elves. Ties go to the earlier elf.
+pub struct TopN {
+    n: usize,
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>,
+}
+
+impl TopN {
+    pub fn new(n: usize) -> Self {
+        TopN {
+            n,
+            heap: BinaryHeap::with_capacity(n.saturating_add(1).min(1 << 16)),
+        }
+    }
+
+    pub fn push(&mut self, elf: Elf) {
+        if self.n == 0 {
+            return;
+        }
+        self.heap.push(Reverse((elf.total, Reverse(elf.index))));
+        if self.heap.len() > self.n {
+            self.heap.pop();
+        }
+    }
+
+    /// The elves kept, most calories first.
+    pub fn into_sorted(self) -> Vec<Elf> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(index)))| Elf { index, total })
+            .collect()
     }
+}
+
+/// The `n` elves carrying the most calories, most first. Only the current
+/// line and `n` elves are kept in memory, however large the input.
+pub fn top_elves<R: BufRead>(reader: R, n: usize) -> Result<Vec<Elf>, ReadError> {
+    let mut top = TopN::new(n);
+    for elf in elves(reader) {
+        top.push(elf?);
+    }
+    Ok(top.into_sorted())
+}
 
-    top_elfs_load
-}
\ No newline at end of file
+/// Like [`top_elves`], for elves already in memory.
+pub fn top_n(elves: &[Elf], n: usize) -> Vec<Elf> {
+    let mut top = TopN::new(n);
+    for &elf in elves {
+        top.push(elf);
+    }
+    top.into_sorted()
+}
+
+/// The calories the elves carry between them, as a `u128` like the totals
+/// in [`stats`].
+pub fn total(elves: &[Elf]) -> u128 {
+    elves.iter().map(|elf| elf.total as u128).sum()
+}
+
+pub fn parse_elves(input: &str) -> Result<Vec<Elf>, ParseError> {
+    let mut grouping = Grouping::default();
+    let mut elves = Vec::new();
+    for line in parser::lines(Day1::DAY, input) {
+        elves.extend(grouping.add(line)?);
+    }
+    elves.extend(grouping.finish());
+    Ok(elves)
+}
+
+/// Statistics on the items each elf carries, keyed by the elf's index.
+pub fn inventory(input: &str, bins: usize) -> Result<Vec<(usize, Summary)>, ParseError> {
+    Ok(stats::summarize(
+        &stats::parse_groups(Day1::DAY, input)?,
+        bins,
+    ))
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<Elf>;
+    type Answer = u128;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_elves(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer {
+        total(&top_n(input, 1))
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer {
+        total(&top_n(input, 3))
+    }
+}
+
+#[test]
+fn test_parse_error() {
+    let err = Day1::parse("1000\n2000\n\n 3x00\n").err().unwrap();
+    assert_eq!((4, 2, "3x00"), (err.line, err.column, err.text.as_str()));
+    let err = Day1::parse("18446744073709551615\n1\n").err().unwrap();
+    assert_eq!(
+        (2, "the elf carries too many calories"),
+        (err.line, err.message.as_str())
+    );
+}
+
+#[test]
+fn test_huge_totals() {
+    let elves = Day1::parse("18446744073709551615\n\n18446744073709551615\n").unwrap();
+    assert_eq!(u64::MAX as u128, Day1::part1(&elves));
+    assert_eq!(2 * u64::MAX as u128, Day1::part2(&elves));
+}
+
+#[test]
+fn test_top_elves() {
+    let elf = |index, total| Elf { index, total };
+    // The last elf counts without a trailing blank line, and repeated blank
+    // lines do not make empty elves.
+    let input = "1\n2\n\n\n3\n\n1\n1\r\n\n4";
+    let reader = io::BufReader::with_capacity(2, input.as_bytes());
+    assert_eq!(
+        vec![elf(3, 4), elf(0, 3), elf(1, 3)],
+        top_elves(reader, 3).unwrap()
+    );
+    assert_eq!(Vec::<Elf>::new(), top_elves(input.as_bytes(), 0).unwrap());
+    assert_eq!(4, top_elves(input.as_bytes(), 10).unwrap().len());
+    assert_eq!(4, parse_elves(input).unwrap().len());
+
+    let mut elves = elves("5\n\nx\n\n6\n".as_bytes());
+    assert_eq!(elf(0, 5), elves.next().unwrap().unwrap());
+    assert!(matches!(elves.next(), Some(Err(ReadError::Parse(err))) if err.line == 3));
+    assert!(elves.next().is_none());
+    let invalid = [b'1', b'\n', 0xff, b'\n'];
+    assert!(matches!(top_elves(&invalid[..], 1), Err(ReadError::Io(_))));
+}
+
+#[test]
+fn test_inventory() {
+    let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+    let inventory = inventory(input, stats::DEFAULT_BINS).unwrap();
+    assert_eq!(5, inventory.len());
+    let (index, summary) = &inventory[3];
+    assert_eq!((3, 3, 24000), (*index, summary.count, summary.total));
+    assert_eq!((8000.0, 8000.0), (summary.mean, summary.median));
+    let totals = parse_elves(input).unwrap();
+    assert!(inventory
+        .iter()
+        .zip(&totals)
+        .all(|((_, summary), elf)| summary.total == elf.total as u128));
+}
+
+/// The `n` elves with the largest totals, largest first and the earlier
+/// elf first on ties, found by sorting every elf's total.
+#[cfg(test)]
+fn reference_top(groups: &[Vec<u32>], n: usize) -> Vec<(usize, u64)> {
+    let mut totals = groups
+        .iter()
+        .map(|group| group.iter().map(|&calories| calories as u64).sum::<u64>())
+        .enumerate()
+        .collect::<Vec<_>>();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    totals.truncate(n);
+    totals
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn test_matches_reference(
+        groups in prop::collection::vec(prop::collection::vec(0u32..100_000, 1..6), 0..40),
+        blank_lines in prop::collection::vec(1usize..4, 40),
+        trailing in prop::sample::select(vec!["", "\n", "\n\n"]),
+        n in 0usize..6,
+    ) {
+        let mut input = String::new();
+        for (i, group) in groups.iter().enumerate() {
+            if i > 0 {
+                input += &"\n".repeat(1 + blank_lines[i]);
+            }
+            input += &group.iter().map(u32::to_string).collect::<Vec<_>>().join("\n");
+        }
+        input += trailing;
+        let top = top_elves(input.as_bytes(), n)
+            .unwrap()
+            .iter()
+            .map(|elf| (elf.index, elf.total))
+            .collect::<Vec<_>>();
+        prop_assert_eq!(reference_top(&groups, n), top);
+
+        let elves = Day1::parse(&input).unwrap();
+        let sum = |n| reference_top(&groups, n).iter().map(|&(_, total)| total as u128).sum::<u128>();
+        prop_assert_eq!((sum(1), sum(3)), (Day1::part1(&elves), Day1::part2(&elves)));
+    }
+}
@@ -1,78 +1,928 @@
-use std::collections::HashMap;
+use crate::error::ParseError;
+use crate::parser::{self, Line};
+use crate::solution::Solution;
+#[cfg(test)]
+use proptest::prelude::*;
+use std::cmp::Reverse;
 
-fn init1() -> (HashMap<&'static str, u32>, HashMap<u8, u32>) {
-    let match_scores = HashMap::from([
-        ("A X", 3),
-        ("A Y", 6),
-        ("A Z", 0),
-        ("B X", 0),
-        ("B Y", 3),
-        ("B Z", 6),
-        ("C X", 6),
-        ("C Y", 0),
-        ("C Z", 3),
-    ]);
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Shape {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 3] = [Shape::Rock, Shape::Paper, Shape::Scissors];
+
+    /// The shape this one beats.
+    pub fn beats(self) -> Shape {
+        match self {
+            Shape::Rock => Shape::Scissors,
+            Shape::Paper => Shape::Rock,
+            Shape::Scissors => Shape::Paper,
+        }
+    }
+
+    /// The shape that beats this one.
+    pub fn loses_to(self) -> Shape {
+        match self {
+            Shape::Rock => Shape::Paper,
+            Shape::Paper => Shape::Scissors,
+            Shape::Scissors => Shape::Rock,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Shape::Rock => "rock",
+            Shape::Paper => "paper",
+            Shape::Scissors => "scissors",
+        }
+    }
+
+    pub fn score(self) -> u32 {
+        match self {
+            Shape::Rock => 1,
+            Shape::Paper => 2,
+            Shape::Scissors => 3,
+        }
+    }
+
+    /// The outcome of playing this shape against `opponent`.
+    pub fn against(self, opponent: Shape) -> Outcome {
+        if self.beats() == opponent {
+            Outcome::Win
+        } else if self.loses_to() == opponent {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// The code for this shape in the opponent's column.
+    pub fn opponent_code(self) -> char {
+        match self {
+            Shape::Rock => 'A',
+            Shape::Paper => 'B',
+            Shape::Scissors => 'C',
+        }
+    }
+
+    /// The shape an opponent code `A`, `B` or `C` stands for.
+    fn from_opponent(code: char) -> Shape {
+        match code {
+            'A' => Shape::Rock,
+            'B' => Shape::Paper,
+            _ => Shape::Scissors,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+
+    pub fn score(self) -> u32 {
+        match self {
+            Outcome::Loss => 0,
+            Outcome::Draw => 3,
+            Outcome::Win => 6,
+        }
+    }
+
+    /// The shape to play against `opponent` to reach this outcome.
+    pub fn response(self, opponent: Shape) -> Shape {
+        match self {
+            Outcome::Loss => opponent.beats(),
+            Outcome::Draw => opponent,
+            Outcome::Win => opponent.loses_to(),
+        }
+    }
+}
+
+/// The second column of the guide, whose meaning is up to each part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Column {
+    X,
+    Y,
+    Z,
+}
+
+impl Column {
+    pub const ALL: [Column; 3] = [Column::X, Column::Y, Column::Z];
+
+    pub fn code(self) -> char {
+        match self {
+            Column::X => 'X',
+            Column::Y => 'Y',
+            Column::Z => 'Z',
+        }
+    }
+
+    /// Part 1: X, Y and Z are rock, paper and scissors.
+    pub fn as_shape(self) -> Shape {
+        match self {
+            Column::X => Shape::Rock,
+            Column::Y => Shape::Paper,
+            Column::Z => Shape::Scissors,
+        }
+    }
+
+    /// Part 2: X, Y and Z mean lose, draw and win.
+    pub fn as_outcome(self) -> Outcome {
+        match self {
+            Column::X => Outcome::Loss,
+            Column::Y => Outcome::Draw,
+            Column::Z => Outcome::Win,
+        }
+    }
+
+    fn from_code(code: char) -> Column {
+        match code {
+            'X' => Column::X,
+            'Y' => Column::Y,
+            _ => Column::Z,
+        }
+    }
+}
+
+/// One line of the strategy guide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Round {
+    pub opponent: Shape,
+    pub column: Column,
+}
+
+/// Parses one `<opponent> <column>` line of the strategy guide.
+pub fn parse_round(line: Line) -> Result<Round, ParseError> {
+    let (opponent, rest) = line.one_of(line.text, "ABC", "expected opponent play A, B or C")?;
+    let rest = line.literal(rest, " ", "expected a space")?;
+    let (column, rest) = line.one_of(rest, "XYZ", "expected X, Y or Z")?;
+    line.end(rest)?;
+    Ok(Round {
+        opponent: Shape::from_opponent(opponent),
+        column: Column::from_code(column),
+    })
+}
+
+/// The score for playing `me` against `opponent`.
+pub fn score_round(opponent: Shape, me: Shape) -> u32 {
+    me.score() + me.against(opponent).score()
+}
+
+/// Scores the guide, choosing each shape to play with `choose`.
+pub fn score_with(rounds: &[Round], choose: impl Fn(&Round) -> Shape) -> u32 {
+    rounds
+        .iter()
+        .map(|round| score_round(round.opponent, choose(round)))
+        .sum()
+}
+
+/// Scores the guide reading the second column as the shape to play.
+pub fn score_by_play(rounds: &[Round]) -> u32 {
+    score_with(rounds, |round| round.column.as_shape())
+}
+
+/// Scores the guide reading the second column as the outcome to reach.
+pub fn score_by_outcome(rounds: &[Round]) -> u32 {
+    score_with(rounds, |round| {
+        round.column.as_outcome().response(round.opponent)
+    })
+}
+
+/// The rules of a rock-paper-scissors-like game as data, with shapes
+/// numbered from 0. Rules files hold `key = value` lines and `#` comments:
+///
+/// ```text
+/// shapes = rock paper scissors spock lizard
+/// # Each shape beats the shapes an odd number of places before it.
+/// beats = cyclic
+/// # Or one line per shape:
+/// # rock beats = scissors lizard
+/// shape scores = 1 2 3 4 5
+/// outcome scores = 0 3 6
+/// opponent codes = A B C D E
+/// player codes = V W X Y Z
+/// # Lose, draw and win, when the second column is an outcome.
+/// outcome codes = X Y Z
+/// ```
+///
+/// Only `shapes` and the dominance relation are required. Shape scores
+/// default to 1, 2, 3..., outcome scores to 0, 3 and 6, opponent codes to
+/// letters from A and player codes to letters ending at Z.
+///
+/// Rules only come from [`Rules::standard`] and [`Rules::parse`], so every
+/// shape beats and loses to another and the codes are unambiguous. Methods
+/// taking shape numbers panic, like slice indexing, on numbers from
+/// `len()` on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    names: Vec<String>,
+    shape_scores: Vec<u32>,
+    /// Indexed by [`Outcome`].
+    outcome_scores: [u32; 3],
+    /// `beats[a][b]` if shape `a` beats shape `b`.
+    beats: Vec<Vec<bool>>,
+    opponent_codes: Vec<char>,
+    /// Second-column codes read as the shape to play, one per shape.
+    player_codes: Vec<char>,
+    /// Second-column codes read as the outcome to reach: lose, draw, win.
+    outcome_codes: [char; 3],
+}
+
+/// A round of the guide read with [`Rules`]: the opponent's shape and the
+/// second column, whose meaning is up to the scorer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Play {
+    opponent: usize,
+    /// Index into the player codes.
+    column: usize,
+}
+
+impl Play {
+    pub fn opponent(&self) -> usize {
+        self.opponent
+    }
+
+    /// The second column as an index into [`Rules::player_codes`].
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+/// A strategy guide parsed with, and scored under, one set of [`Rules`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Guide<'a> {
+    rules: &'a Rules,
+    plays: Vec<Play>,
+}
+
+/// Distinct single-character codes, one per item of `expected`.
+fn parse_codes(line: &Line, value: &str, expected: usize) -> Result<Vec<char>, ParseError> {
+    let mut codes = Vec::new();
+    for code in value.split_whitespace() {
+        let mut chars = code.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return Err(line.error(code, "expected a single-character code"));
+        };
+        if codes.contains(&c) {
+            return Err(line.error(code, "duplicate code"));
+        }
+        codes.push(c);
+    }
+    if codes.len() != expected {
+        return Err(line.error(value, &format!("expected {} codes", expected)));
+    }
+    Ok(codes)
+}
+
+fn parse_scores(line: &Line, value: &str, expected: usize) -> Result<Vec<u32>, ParseError> {
+    let scores = value
+        .split_whitespace()
+        .map(|score| line.int(score, "expected a score"))
+        .collect::<Result<Vec<_>, _>>()?;
+    if scores.len() != expected {
+        return Err(line.error(value, &format!("expected {} scores", expected)));
+    }
+    Ok(scores)
+}
+
+impl Rules {
+    /// Rock, paper and scissors as in the puzzle, built from [`Shape`],
+    /// [`Outcome`] and [`Column`].
+    pub fn standard() -> Self {
+        let column_code = |reads_as: &dyn Fn(Column) -> bool| {
+            Column::ALL
+                .into_iter()
+                .find(|&column| reads_as(column))
+                .map(Column::code)
+                .expect("every shape and outcome has a column")
+        };
+        Rules {
+            names: Shape::ALL.map(|shape| shape.name().to_owned()).to_vec(),
+            shape_scores: Shape::ALL.map(Shape::score).to_vec(),
+            outcome_scores: Outcome::ALL.map(Outcome::score),
+            beats: Shape::ALL
+                .map(|a| Shape::ALL.map(|b| a.against(b) == Outcome::Win).to_vec())
+                .to_vec(),
+            opponent_codes: Shape::ALL.map(Shape::opponent_code).to_vec(),
+            player_codes: Shape::ALL
+                .map(|shape| column_code(&|column| column.as_shape() == shape))
+                .to_vec(),
+            outcome_codes: Outcome::ALL
+                .map(|outcome| column_code(&|column| column.as_outcome() == outcome)),
+        }
+    }
+
+    /// `names` with the default scores and codes and no dominance yet.
+    fn with_defaults(names: Vec<String>) -> Self {
+        let n = names.len();
+        Rules {
+            shape_scores: (1..=n as u32).collect(),
+            outcome_scores: Outcome::ALL.map(Outcome::score),
+            beats: vec![vec![false; n]; n],
+            opponent_codes: (b'A'..b'A' + n as u8).map(char::from).collect(),
+            player_codes: (b'Z' + 1 - n as u8..=b'Z').map(char::from).collect(),
+            outcome_codes: ['X', 'Y', 'Z'],
+            names,
+        }
+    }
+
+    /// Parses a rules file; see [`Rules`] for the format.
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut entries = Vec::new();
+        for line in parser::lines(Day2::DAY, text) {
+            let trimmed = line.text.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.key_value("=", "expected 'key = value'")?;
+            entries.push((line, key, value));
+        }
+        let Some(&(shapes_line, _, shapes)) = entries.iter().find(|(_, key, _)| *key == "shapes")
+        else {
+            return Err(ParseError::new(Day2::DAY, 1, 1, "", "missing 'shapes'"));
+        };
+        let names = shapes.split_whitespace().collect::<Vec<_>>();
+        if names.len() < 3 || names.len() > 26 {
+            return Err(shapes_line.error(shapes, "expected 3 to 26 shapes"));
+        }
+        if let Some(i) = (1..names.len()).find(|&i| names[..i].contains(&names[i])) {
+            return Err(shapes_line.error(names[i], "duplicate shape"));
+        }
+        let mut rules = Rules::with_defaults(names.iter().map(|name| name.to_string()).collect());
+        let n = rules.len();
+        // Where the codes were set, to report outcome codes that are not
+        // player codes.
+        let mut player_codes_at = None;
+        let mut outcome_codes_at = None;
 
-    let play_scores = HashMap::from([(b'X', 1), (b'Y', 2), (b'Z', 3)]);
+        for (line, key, value) in &entries {
+            match *key {
+                "shapes" => {}
+                "beats" if *value == "cyclic" => {
+                    if n.is_multiple_of(2) {
+                        return Err(line.error(value, "cyclic rules need an odd number of shapes"));
+                    }
+                    for (a, row) in rules.beats.iter_mut().enumerate() {
+                        for (b, beats) in row.iter_mut().enumerate() {
+                            *beats = (a + n - b) % n % 2 == 1;
+                        }
+                    }
+                }
+                "shape scores" => rules.shape_scores = parse_scores(line, value, n)?,
+                "outcome scores" => {
+                    let scores = parse_scores(line, value, 3)?;
+                    rules.outcome_scores = [scores[0], scores[1], scores[2]];
+                }
+                "opponent codes" => rules.opponent_codes = parse_codes(line, value, n)?,
+                "player codes" => {
+                    rules.player_codes = parse_codes(line, value, n)?;
+                    player_codes_at = Some((line, value));
+                }
+                "outcome codes" => {
+                    let codes = parse_codes(line, value, 3)?;
+                    rules.outcome_codes = [codes[0], codes[1], codes[2]];
+                    outcome_codes_at = Some((line, value));
+                }
+                _ => {
+                    let Some(a) = key
+                        .strip_suffix(" beats")
+                        .and_then(|name| rules.shape(name))
+                    else {
+                        return Err(line.error(key, "unknown key"));
+                    };
+                    for name in value.split_whitespace() {
+                        let b = rules
+                            .shape(name)
+                            .ok_or_else(|| line.error(name, "unknown shape"))?;
+                        if a == b || rules.beats[b][a] {
+                            let message = format!("{} and {} beat each other", names[a], name);
+                            return Err(line.error(name, &message));
+                        }
+                        rules.beats[a][b] = true;
+                    }
+                }
+            }
+        }
 
-    (match_scores, play_scores)
+        // Every shape must beat and lose to some other shape.
+        for (a, name) in names.iter().enumerate() {
+            if !rules.beats[a].contains(&true) {
+                return Err(shapes_line.error(name, &format!("{} beats no shape", name)));
+            }
+            if !rules.beats.iter().any(|row| row[a]) {
+                return Err(shapes_line.error(name, &format!("no shape beats {}", name)));
+            }
+        }
+        if let Some(&code) = rules
+            .outcome_codes
+            .iter()
+            .find(|code| !rules.player_codes.contains(code))
+        {
+            let message = format!("outcome code {} is not a player code", code);
+            let error = match (outcome_codes_at, player_codes_at) {
+                (Some((line, value)), _) => {
+                    let text = value
+                        .split_whitespace()
+                        .find(|text| text.starts_with(code))
+                        .unwrap_or(value);
+                    line.error(text, &message)
+                }
+                (None, Some((line, value))) => line.error(value, &message),
+                (None, None) => shapes_line.error(shapes, &message),
+            };
+            return Err(error);
+        }
+        Ok(rules)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn shape_scores(&self) -> &[u32] {
+        &self.shape_scores
+    }
+
+    /// Indexed by [`Outcome`].
+    pub fn outcome_scores(&self) -> [u32; 3] {
+        self.outcome_scores
+    }
+
+    pub fn opponent_codes(&self) -> &[char] {
+        &self.opponent_codes
+    }
+
+    /// Second-column codes read as the shape to play, one per shape.
+    pub fn player_codes(&self) -> &[char] {
+        &self.player_codes
+    }
+
+    /// Second-column codes read as the outcome to reach: lose, draw, win.
+    pub fn outcome_codes(&self) -> [char; 3] {
+        self.outcome_codes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn shape(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|shape| shape == name)
+    }
+
+    /// The outcome of playing `me` against `opponent`.
+    pub fn outcome(&self, opponent: usize, me: usize) -> Outcome {
+        if self.beats[me][opponent] {
+            Outcome::Win
+        } else if self.beats[opponent][me] {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// The score for playing `me` against `opponent`, in `u64` so that no
+    /// pair of scores from a rules file can overflow it.
+    pub fn score(&self, opponent: usize, me: usize) -> u64 {
+        let outcome = self.outcome(opponent, me) as usize;
+        self.shape_scores[me] as u64 + self.outcome_scores[outcome] as u64
+    }
+
+    /// The best-scoring shape that reaches `outcome` against `opponent`;
+    /// the first one on ties.
+    pub fn response(&self, opponent: usize, outcome: Outcome) -> usize {
+        (0..self.len())
+            .filter(|&me| self.outcome(opponent, me) == outcome)
+            .max_by_key(|&me| (self.shape_scores[me], Reverse(me)))
+            .expect("checked by parse")
+    }
+
+    /// The best-scoring shape against `opponent`; the first one on ties.
+    pub fn best_response(&self, opponent: usize) -> usize {
+        (0..self.len())
+            .max_by_key(|&me| (self.score(opponent, me), Reverse(me)))
+            .expect("checked by parse")
+    }
+
+    /// Parses a strategy guide written with these rules' codes.
+    pub fn parse_guide(&self, input: &str) -> Result<Guide<'_>, ParseError> {
+        let opponent_codes = self.opponent_codes.iter().collect::<String>();
+        let player_codes = self.player_codes.iter().collect::<String>();
+        let position = |codes: &[char], code| codes.iter().position(|&c| c == code);
+        let plays = parser::each_line(Day2::DAY, input, |line| {
+            let (opponent, rest) =
+                line.one_of(line.text, &opponent_codes, "expected an opponent code")?;
+            let rest = line.literal(rest, " ", "expected a space")?;
+            let (column, rest) = line.one_of(rest, &player_codes, "expected a player code")?;
+            line.end(rest)?;
+            // `one_of` only accepts the codes searched for here.
+            let invalid = || line.error(line.text, "unknown code");
+            Ok(Play {
+                opponent: position(&self.opponent_codes, opponent).ok_or_else(invalid)?,
+                column: position(&self.player_codes, column).ok_or_else(invalid)?,
+            })
+        })?;
+        Ok(Guide { rules: self, plays })
+    }
+
+    /// Describes a decoding such as `X=rock Y=paper Z=scissors`.
+    pub fn describe(&self, decoding: &[usize]) -> String {
+        let codes = self
+            .player_codes
+            .iter()
+            .zip(decoding)
+            .map(|(code, &shape)| {
+                let name = self.names.get(shape).map_or("?", String::as_str);
+                format!("{}={}", code, name)
+            })
+            .collect::<Vec<_>>();
+        codes.join(" ")
+    }
+}
+
+impl Guide<'_> {
+    pub fn rules(&self) -> &Rules {
+        self.rules
+    }
+
+    pub fn plays(&self) -> &[Play] {
+        &self.plays
+    }
+
+    /// Scores the guide, choosing each shape to play with `choose`.
+    fn score_with(&self, choose: impl Fn(&Play) -> usize) -> u64 {
+        self.plays
+            .iter()
+            .map(|play| self.rules.score(play.opponent, choose(play)))
+            .sum()
+    }
+
+    /// Scores the guide reading the second column as the shape to play.
+    pub fn score_by_play(&self) -> u64 {
+        self.score_with(|play| play.column)
+    }
+
+    /// Scores the guide reading the second column as the outcome to reach;
+    /// player codes that are not outcome codes are played as shapes.
+    pub fn score_by_outcome(&self) -> u64 {
+        let rules = self.rules;
+        self.score_with(|play| {
+            let code = rules.player_codes[play.column];
+            match rules.outcome_codes.iter().position(|&c| c == code) {
+                Some(i) => rules.response(play.opponent, Outcome::ALL[i]),
+                None => play.column,
+            }
+        })
+    }
+
+    /// The score-maximizing shape for each round, ignoring the second column.
+    pub fn optimal_plays(&self) -> Vec<usize> {
+        self.plays
+            .iter()
+            .map(|play| self.rules.best_response(play.opponent))
+            .collect()
+    }
+
+    pub fn analyze(&self) -> Analysis {
+        Analysis {
+            by_play: self.score_by_play(),
+            by_outcome: self.score_by_outcome(),
+            optimal: self.score_with(|play| self.rules.best_response(play.opponent)),
+        }
+    }
+
+    /// Every way to read the player codes as distinct shapes, where
+    /// `decoding[i]` is the shape for `player_codes()[i]`, with the guide's
+    /// score under it; best first. `None` beyond [`MAX_DECODING_SHAPES`].
+    pub fn decodings(&self) -> Option<Vec<(Vec<usize>, u64)>> {
+        let n = self.rules.len();
+        if n > MAX_DECODING_SHAPES {
+            return None;
+        }
+        // How often each opponent shape meets each player code.
+        let mut counts = vec![vec![0u64; n]; n];
+        for play in &self.plays {
+            counts[play.opponent][play.column] += 1;
+        }
+        let mut decodings = permutations(n)
+            .into_iter()
+            .map(|decoding| {
+                let score = (0..n)
+                    .flat_map(|opponent| (0..n).map(move |column| (opponent, column)))
+                    .map(|(opponent, column)| {
+                        counts[opponent][column] * self.rules.score(opponent, decoding[column])
+                    })
+                    .sum();
+                (decoding, score)
+            })
+            .collect::<Vec<_>>();
+        decodings.sort_by_key(|&(_, score)| Reverse(score));
+        Some(decodings)
+    }
+}
+
+/// The most shapes [`Guide::decodings`] tries every permutation of.
+pub const MAX_DECODING_SHAPES: usize = 8;
+
+/// Every permutation of `0..n`, in lexicographic order.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![Vec::new()];
+    }
+    let mut all = Vec::new();
+    for first in 0..n {
+        for rest in permutations(n - 1) {
+            let mut permutation = vec![first];
+            permutation.extend(rest.into_iter().map(|i| if i >= first { i + 1 } else { i }));
+            all.push(permutation);
+        }
+    }
+    all
+}
+
+/// A guide's scores under both readings of its second column, next to the
+/// best score any sequence of plays could reach against the same opponent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Analysis {
+    pub by_play: u64,
+    pub by_outcome: u64,
+    pub optimal: u64,
 }
 
-fn init2() -> (
-    HashMap<&'static str, u8>,
-    HashMap<u8, u32>,
-    HashMap<u8, u32>,
-) {
-    let play_responses = HashMap::from([
-        ("A X", b'C'),
-        ("A Y", b'A'),
-        ("A Z", b'B'),
-        ("B X", b'A'),
-        ("B Y", b'B'),
-        ("B Z", b'C'),
-        ("C X", b'B'),
-        ("C Y", b'C'),
-        ("C Z", b'A'),
-    ]);
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Round>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parser::each_line(Self::DAY, input, parse_round)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer {
+        score_by_play(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer {
+        score_by_outcome(input)
+    }
+}
 
-    let match_scores = HashMap::from([(b'X', 0), (b'Y', 3), (b'Z', 6)]);
-    let play_scores = HashMap::from([(b'A', 1), (b'B', 2), (b'C', 3)]);
+#[test]
+fn test_model() {
+    for shape in Shape::ALL {
+        assert_eq!(shape, shape.beats().loses_to());
+        assert_eq!(Outcome::Win, shape.against(shape.beats()));
+        assert_eq!(Outcome::Loss, shape.against(shape.loses_to()));
+        assert_eq!(Outcome::Draw, shape.against(shape));
+        for outcome in [Outcome::Loss, Outcome::Draw, Outcome::Win] {
+            assert_eq!(outcome, outcome.response(shape).against(shape));
+        }
+    }
+    let guide = Day2::parse("A Y\nB X\nC Z\n").unwrap();
+    assert_eq!(
+        Round {
+            opponent: Shape::Rock,
+            column: Column::Y
+        },
+        guide[0]
+    );
+    assert_eq!((15, 12), (score_by_play(&guide), score_by_outcome(&guide)));
+}
 
-    (play_responses, match_scores, play_scores)
+#[test]
+fn test_parse_error() {
+    let err = Day2::parse("A Y\nB W\n").err().unwrap();
+    assert_eq!((2, 3, "W"), (err.line, err.column, err.text.as_str()));
+    let err = Day2::parse("A Y\nC Z \n").err().unwrap();
+    assert_eq!((2, 4, " "), (err.line, err.column, err.text.as_str()));
 }
 
-pub fn solve() -> u32 {
-    let input = include_str!("../inputs/day2.txt");
+#[test]
+fn test_rules() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("rules");
+    let load = |name: &str| Rules::parse(&std::fs::read_to_string(dir.join(name)).unwrap());
+    assert_eq!(Rules::standard(), load("rps.rules").unwrap());
 
-    let (match_scores, play_scores) = init1();
+    // The puzzle's typed model and the rules file agree on the example.
+    let rules = load("rps.rules").unwrap();
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    let example = std::fs::read_to_string(dir.join("day2.txt")).unwrap();
+    let rounds = Day2::parse(&example).unwrap();
+    let guide = rules.parse_guide(&example).unwrap();
+    assert_eq!(
+        (Day2::part1(&rounds) as u64, Day2::part2(&rounds) as u64),
+        (guide.score_by_play(), guide.score_by_outcome())
+    );
 
-    let mut score = 0u32;
-    for line in input.lines() {
-        let my_play = line.as_bytes()[line.len() - 1];
-        score += play_scores
-            .get(&my_play)
-            .expect(format!("Unsupported outcome: '{}'", line).as_str());
-        score += match_scores.get(line).unwrap();
+    let rules = load("rpsls.rules").unwrap();
+    let shape = |name| rules.shape(name).unwrap();
+    for (a, b) in [
+        ("scissors", "paper"),
+        ("lizard", "spock"),
+        ("spock", "rock"),
+        ("rock", "lizard"),
+    ] {
+        assert_eq!(
+            Outcome::Win,
+            rules.outcome(shape(b), shape(a)),
+            "{} beats {}",
+            a,
+            b
+        );
     }
+    assert_eq!(Outcome::Draw, rules.outcome(shape("spock"), shape("spock")));
+    // Both paper and spock beat rock; spock scores more.
+    assert_eq!(shape("spock"), rules.response(shape("rock"), Outcome::Win));
+    let guide = rules.parse_guide("A Y\nB X\nC Z\nE V\n").unwrap();
+    // Spock against rock, scissors against paper, lizard against scissors,
+    // rock against lizard: 10 + 9 + 5 + 7.
+    assert_eq!(31, guide.score_by_play());
+    let play = guide.plays()[3];
+    assert_eq!(
+        (4, 'V'),
+        (play.opponent(), rules.player_codes()[play.column()])
+    );
 
-    score
+    let err = |text: &str| Rules::parse(text).unwrap_err();
+    assert_eq!("missing 'shapes'", err("beats = cyclic\n").message);
+    let e = err("shapes = a b c d\nbeats = cyclic\n");
+    assert_eq!(
+        (2, "cyclic rules need an odd number of shapes"),
+        (e.line, e.message.as_str())
+    );
+    let e = err("shapes = a b c\na beats = b d\n");
+    assert_eq!(
+        (2, 13, "unknown shape"),
+        (e.line, e.column, e.message.as_str())
+    );
+    let e = err("shapes = a b c\na beats = b\nb beats = a c\n");
+    assert_eq!(
+        (3, 11, "b and a beat each other"),
+        (e.line, e.column, e.message.as_str())
+    );
+    let e = err("shapes = a b c\na beats = a\n");
+    assert_eq!(
+        (2, 11, "a and a beat each other"),
+        (e.line, e.column, e.message.as_str())
+    );
+    let e = err("shapes = a b c\na beats = b c\nb beats = c\n");
+    assert_eq!(
+        (1, 10, "no shape beats a"),
+        (e.line, e.column, e.message.as_str())
+    );
+    let e = err("shapes = a b c\nbeats = cyclic\nplayer codes = X Y X\n");
+    assert_eq!(
+        (3, 20, "duplicate code"),
+        (e.line, e.column, e.message.as_str())
+    );
+    let e = err("shapes = a b c\nbeats = cyclic\nshape scores = 1 2\n");
+    assert_eq!((3, "expected 3 scores"), (e.line, e.message.as_str()));
+    let e = err("shapes = a b c\nbeats = cyclic\noutcome codes = X Y A\n");
+    assert_eq!(
+        (3, 21, "outcome code A is not a player code"),
+        (e.line, e.column, e.message.as_str())
+    );
+    let e = err("shapes = a b c\nplayer codes = P Q R\nbeats = cyclic\n");
+    assert_eq!(
+        (2, "outcome code X is not a player code"),
+        (e.line, e.message.as_str())
+    );
+    assert_eq!("unknown key", err("shapes = a b c\ncolour = red\n").message);
+
+    let rules =
+        Rules::parse("shapes = a b c\nbeats = cyclic\nshape scores = 4294967295 1 1\n").unwrap();
+    let guide = rules.parse_guide("A X\n").unwrap();
+    assert_eq!(4294967298, guide.score_by_play());
 }
 
-pub fn solve2() -> u32 {
-    let input = include_str!("../inputs/day2.txt");
+#[test]
+fn test_analysis() {
+    let rules = Rules::standard();
+    let guide = rules.parse_guide("A Y\nB X\nC Z\n").unwrap();
+    // Paper against rock, scissors against paper, rock against scissors.
+    assert_eq!(vec![1, 2, 0], guide.optimal_plays());
+    assert_eq!(
+        Analysis {
+            by_play: 15,
+            by_outcome: 12,
+            optimal: 24
+        },
+        guide.analyze()
+    );
 
-    let (play_responses, match_scores, play_scores) = init2();
+    let decodings = guide.decodings().unwrap();
+    assert_eq!(6, decodings.len());
+    assert_eq!((vec![2, 1, 0], 24), decodings[0]);
+    assert!(decodings.contains(&(vec![0, 1, 2], 15)));
+    assert_eq!("X=scissors Y=paper Z=rock", rules.describe(&decodings[0].0));
+    assert_eq!(120, permutations(5).len());
+    assert_eq!(vec![vec![0, 1], vec![1, 0]], permutations(2));
+}
 
-    let mut score = 0u32;
-    for line in input.lines() {
-        let my_play = play_responses.get(line).unwrap();
-        let match_outcome = line.as_bytes()[line.len() - 1];
+/// Scores a round from shape indices (rock 0, paper 1, scissors 2): each
+/// shape beats the one before it.
+#[cfg(test)]
+fn reference_score(opponent: u32, me: u32) -> u32 {
+    let outcome = if me == opponent {
+        3
+    } else if me == (opponent + 1) % 3 {
+        6
+    } else {
+        0
+    };
+    me + 1 + outcome
+}
+
+/// A rules file from `rules/` with random edits, and a guide in codes any
+/// of them might use.
+#[cfg(test)]
+fn mangled_rules() -> impl Strategy<Value = (String, String)> {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("rules");
+    let files = ["rps.rules", "rpsls.rules"]
+        .map(|name| std::fs::read_to_string(dir.join(name)).unwrap())
+        .to_vec();
+    let edit = (
+        any::<prop::sample::Index>(),
+        prop::option::of(prop::sample::select(
+            " \n=#0123456789ABCXYZabcrockpaperscissors"
+                .chars()
+                .collect::<Vec<_>>(),
+        )),
+    );
+    (
+        prop::sample::select(files),
+        prop::collection::vec(edit, 0..8),
+        "([A-E] [V-Z]\n){0,10}",
+    )
+        .prop_map(|(rules, edits, guide)| {
+            let mut chars = rules.chars().collect::<Vec<_>>();
+            for (at, replacement) in edits {
+                let at = at.index(chars.len());
+                match replacement {
+                    Some(ch) => chars[at] = ch,
+                    None => {
+                        chars.remove(at);
+                    }
+                }
+            }
+            (chars.into_iter().collect(), guide)
+        })
+}
 
-        score += match_scores.get(&match_outcome).unwrap();
-        score += play_scores
-            .get(&my_play)
-            .expect(format!("Unsupported outcome: '{}'", line).as_str());
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn test_no_rules_panic((rules, guide) in mangled_rules()) {
+        if let Ok(rules) = Rules::parse(&rules) {
+            if let Ok(guide) = rules.parse_guide(&guide) {
+                guide.analyze();
+                if let Some(decodings) = guide.decodings() {
+                    prop_assert!(decodings.iter().all(|(decoding, _)| !rules.describe(decoding).is_empty()));
+                }
+            }
+        }
     }
 
-    score
+    #[test]
+    fn test_matches_reference(rounds in prop::collection::vec((0u32..3, 0u32..3), 0..50)) {
+        let input = rounds
+            .iter()
+            .map(|&(opponent, column)| {
+                format!("{} {}\n", (b'A' + opponent as u8) as char, (b'X' + column as u8) as char)
+            })
+            .collect::<String>();
+        let guide = Day2::parse(&input).unwrap();
+        let by_play = rounds.iter().map(|&(opponent, me)| reference_score(opponent, me));
+        // X loses, Y draws and Z wins.
+        let by_outcome = rounds
+            .iter()
+            .map(|&(opponent, outcome)| reference_score(opponent, (opponent + outcome + 2) % 3));
+        prop_assert_eq!(by_play.sum::<u32>(), Day2::part1(&guide));
+        prop_assert_eq!(by_outcome.sum::<u32>(), Day2::part2(&guide));
+        let rules = Rules::standard();
+        let plays = rules.parse_guide(&input).unwrap();
+        prop_assert_eq!(Day2::part1(&guide) as u64, plays.score_by_play());
+        prop_assert_eq!(Day2::part2(&guide) as u64, plays.score_by_outcome());
+        let analysis = plays.analyze();
+        let decodings = plays.decodings().unwrap();
+        prop_assert!(analysis.optimal >= analysis.by_play.max(analysis.by_outcome));
+        prop_assert!(decodings.iter().all(|&(_, score)| score <= analysis.optimal));
+        prop_assert!(decodings.contains(&(vec![0, 1, 2], analysis.by_play)));
+    }
 }
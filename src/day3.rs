@@ -1,3 +1,8 @@
+use crate::error::ParseError;
+use crate::parser::{self, Line};
+use crate::solution::Solution;
+#[cfg(test)]
+use proptest::prelude::*;
 use std::collections::HashSet;
 
 fn setify(comp: &str) -> HashSet<u8> {
@@ -8,41 +13,76 @@ fn setify(comp: &str) -> HashSet<u8> {
     set
 }
 
-fn value_for(byte: u8) -> u32 {
-    if byte >= b'a' && byte <= b'z' {
+/// The priority of an item: `a`-`z` are 1-26, `A`-`Z` are 27-52.
+pub fn value_for(byte: u8) -> u32 {
+    if byte.is_ascii_lowercase() {
         (1 + byte - b'a') as u32
     } else {
         (27 + byte - b'A') as u32
     }
 }
 
-pub fn solve(input: &str) -> u32 {
+pub fn parse_rucksack(line: Line) -> Result<String, ParseError> {
+    line.chars_where(
+        line.text,
+        |c| c.is_ascii_alphabetic(),
+        "expected an item letter",
+    )?;
+    if !line.text.len().is_multiple_of(2) {
+        return Err(line.error(line.text, "compartments differ in size"));
+    }
+    let (comp1, comp2) = line.text.split_at(line.text.len() / 2);
+    if setify(comp1).is_disjoint(&setify(comp2)) {
+        return Err(line.error(line.text, "no item is in both compartments"));
+    }
+    Ok(line.text.to_owned())
+}
+
+pub fn parse_rucksacks(input: &str) -> Result<Vec<String>, ParseError> {
+    let mut rucksacks = Vec::new();
+    for line in parser::lines(Day3::DAY, input) {
+        rucksacks.push(parse_rucksack(line)?);
+        if rucksacks.len().is_multiple_of(3) {
+            let group = &rucksacks[rucksacks.len() - 3..];
+            let common = setify(&group[0])
+                .iter()
+                .filter(|b| setify(&group[1]).contains(b) && setify(&group[2]).contains(b))
+                .count();
+            if common != 1 {
+                let message = format!("group shares {} items instead of one badge", common);
+                return Err(line.error(line.text, &message));
+            }
+        }
+    }
+    Ok(rucksacks)
+}
+
+pub fn sum_common_items(rucksacks: &[String]) -> u32 {
     let mut sum = 0;
-    for line in input.lines() {
+    for line in rucksacks {
         let (comp1, comp2) = line.split_at(line.len() / 2);
         let comp1set = setify(comp1);
         let comp2set = setify(comp2);
         let mut intersection = comp1set.intersection(&comp2set);
-        let common = intersection.nth(0).unwrap();
+        let common = intersection.next().unwrap();
         sum += value_for(*common)
     }
 
     sum
 }
 
-pub fn solve2(input: &str) -> u32 {
+pub fn sum_badges(rucksacks: &[String]) -> u32 {
     let mut state = 0;
     let mut set1 = HashSet::new();
     let mut set2 = HashSet::new();
-    let mut set3 = HashSet::new();
     let mut sum = 0;
-    for line in input.lines() {
+    for line in rucksacks {
         if state == 0 {
             set1 = setify(line);
         } else if state == 1 {
             set2 = setify(line);
         } else {
-            set3 = setify(line);
+            let set3 = setify(line);
             let common: Vec<u8> = set1
                 .iter()
                 .map(|b| b.to_owned())
@@ -51,7 +91,7 @@ pub fn solve2(input: &str) -> u32 {
                 .map(|b| b.to_owned())
                 .collect::<Vec<u8>>();
             assert_eq!(1, common.len());
-            let common = common.get(0).unwrap();
+            let common = common.first().unwrap();
             sum += value_for(*common);
         }
         state = (state + 1) % 3;
@@ -60,16 +100,98 @@ pub fn solve2(input: &str) -> u32 {
     sum
 }
 
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Vec<String>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_rucksacks(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer {
+        sum_common_items(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer {
+        sum_badges(input)
+    }
+}
+
 #[test]
-fn day3_test() {
-    let day3_test_input = r#"
-vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw
-    "#
-    .trim();
-    assert_eq!(solve(day3_test_input), 157);
+fn test_parse_error() {
+    let err = Day3::parse("vJrwpWtwJgWrhcsFMMfFFhFp\nabc1").err().unwrap();
+    assert_eq!((2, 4, "1"), (err.line, err.column, err.text.as_str()));
+    let err = Day3::parse("abcdef").err().unwrap();
+    assert_eq!("no item is in both compartments", err.message);
+}
+
+/// The item in both halves of each rucksack, and the badge of each group,
+/// found by searching the strings directly.
+#[cfg(test)]
+fn reference(rucksacks: &[String]) -> (u32, u32) {
+    let priority = |item: char| {
+        let letters = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        letters.find(item).unwrap() as u32 + 1
+    };
+    let common = rucksacks.iter().map(|rucksack| {
+        let (left, right) = rucksack.split_at(rucksack.len() / 2);
+        priority(left.chars().find(|&item| right.contains(item)).unwrap())
+    });
+    let badges = rucksacks.chunks(3).map(|group| {
+        let badge = group[0]
+            .chars()
+            .find(|&item| group[1].contains(item) && group[2].contains(item));
+        priority(badge.unwrap())
+    });
+    (common.sum(), badges.sum())
+}
+
+/// A group of three rucksacks. The alphabet is shuffled into a badge and a
+/// pool of 17 letters per rucksack, so the badge is the only letter all
+/// three share. Each rucksack's halves share one letter from its own pool,
+/// or the badge.
+#[cfg(test)]
+fn groups() -> impl Strategy<Value = Vec<String>> {
+    let letters = (b'a'..=b'z').chain(b'A'..=b'Z').collect::<Vec<u8>>();
+    let rucksack = (any::<bool>(), 0usize..8, 0usize..8);
+    (
+        Just(letters).prop_shuffle(),
+        prop::collection::vec(rucksack, 3),
+    )
+        .prop_map(|(letters, rucksacks)| {
+            let badge = letters[0];
+            let mut group = Vec::new();
+            for (pool, (shares_badge, left_len, right_len)) in
+                letters[1..].chunks(17).zip(rucksacks)
+            {
+                let common = if shares_badge { badge } else { pool[0] };
+                let mut left = vec![common];
+                left.extend(&pool[1..1 + left_len]);
+                let mut right = vec![common];
+                right.extend(&pool[9..9 + right_len]);
+                if !shares_badge {
+                    left.push(badge);
+                }
+                let len = left.len().max(right.len());
+                left.resize(len, left[0]);
+                right.resize(len, right[0]);
+                group.push(String::from_utf8([left, right].concat()).unwrap());
+            }
+            group
+        })
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn test_matches_reference(groups in prop::collection::vec(groups(), 0..10)) {
+        let rucksacks = groups.concat();
+        let input = rucksacks.join("\n");
+        let parsed = Day3::parse(&input).unwrap();
+        prop_assert_eq!(reference(&rucksacks), (Day3::part1(&parsed), Day3::part2(&parsed)));
+    }
 }
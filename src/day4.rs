@@ -1,63 +1,125 @@
-use std::cmp::min;
-use std::collections::HashSet;
+use crate::error::ParseError;
+use crate::parser::{self, Line};
+use crate::solution::Solution;
+#[cfg(test)]
+use proptest::prelude::*;
 use std::ops::RangeInclusive;
-use std::str::FromStr;
-
-fn parse_range_str(range_str: &str) -> RangeInclusive<u32> {
-    let split = range_str.split("-").collect::<Vec<&str>>();
-    assert_eq!(2, split.len());
-    let range_start = u32::from_str(split.get(0).unwrap()).unwrap();
-    let range_end = u32::from_str(split.get(1).unwrap()).unwrap();
-    (range_start..=range_end).into()
-}
-
-fn parse_line(line: &str) -> (RangeInclusive<u32>, RangeInclusive<u32>) {
-    let split = line.split(",").collect::<Vec<&str>>();
-    assert_eq!(2, split.len());
-    let range1 = parse_range_str(split.get(0).unwrap());
-    let range2 = parse_range_str(split.get(1).unwrap());
-    (range1, range2)
-}
-
-pub fn solve(input: &str) -> u32 {
-    let mut count = 0u32;
-    for line in input.lines() {
-        let (range1, range2) = parse_line(line);
-        let smallest_range_size = min(range1.clone().count(), range2.clone().count());
-        let set1 = range1.collect::<HashSet<u32>>();
-        let set2 = range2.collect::<HashSet<u32>>();
-        if set1.intersection(&set2).count() == smallest_range_size {
-            count += 1;
-        }
+
+/// The section ranges assigned to a pair of elves.
+pub type Assignment = (RangeInclusive<u32>, RangeInclusive<u32>);
+
+/// Parses `range_str`, a slice of `line` such as `2-4`.
+pub fn parse_range_str<'a>(
+    line: Line<'a>,
+    range_str: &'a str,
+) -> Result<RangeInclusive<u32>, ParseError> {
+    let (start, end) = line.split_once(range_str, "-", "expected a range like '2-4'")?;
+    let range_start = line.int(start, "expected a section number")?;
+    let range_end = line.int(end, "expected a section number")?;
+    if range_start > range_end {
+        return Err(line.error(range_str, "range ends before it starts"));
     }
+    Ok(range_start..=range_end)
+}
+
+/// Parses a line such as `2-4,6-8`.
+pub fn parse_line(line: Line) -> Result<Assignment, ParseError> {
+    let (first, second) =
+        line.split_once(line.text, ",", "expected two ranges separated by ','")?;
+    Ok((
+        parse_range_str(line, first)?,
+        parse_range_str(line, second)?,
+    ))
+}
+
+pub fn count_contained(assignments: &[Assignment]) -> u32 {
+    let contains = |outer: &RangeInclusive<u32>, inner: &RangeInclusive<u32>| {
+        outer.start() <= inner.start() && inner.end() <= outer.end()
+    };
+    assignments
+        .iter()
+        .filter(|(range1, range2)| contains(range1, range2) || contains(range2, range1))
+        .count() as u32
+}
 
-    count
+pub fn count_overlapping(assignments: &[Assignment]) -> u32 {
+    assignments
+        .iter()
+        .filter(|(range1, range2)| range1.start() <= range2.end() && range2.start() <= range1.end())
+        .count() as u32
 }
 
-pub fn solve2(input: &str) -> u32 {
-    let mut count = 0u32;
-    for line in input.lines() {
-        let (range1, range2) = parse_line(line);
-        let set1 = range1.collect::<HashSet<u32>>();
-        let set2 = range2.collect::<HashSet<u32>>();
-        if set1.intersection(&set2).count() > 0 {
-            count += 1;
-        }
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Vec<Assignment>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parser::each_line(Self::DAY, input, parse_line)
     }
 
-    count
+    fn part1(input: &Self::Input) -> Self::Answer {
+        count_contained(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer {
+        count_overlapping(input)
+    }
 }
 
 #[test]
-fn test_day4() {
-    let day4_input = r#"
-2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8
-    "#
-    .trim();
-    assert_eq!(2, solve(day4_input));
+fn test_parse_error() {
+    let err = Day4::parse("2-4,6-8\n2-3,x-5").err().unwrap();
+    assert_eq!((2, 5, "x"), (err.line, err.column, err.text.as_str()));
+    let err = Day4::parse("2-4;6-8").err().unwrap();
+    assert_eq!((1, 1), (err.line, err.column));
+}
+
+#[test]
+fn test_huge_ranges() {
+    let assignments = Day4::parse("1-4000000000,2-3999999999\n5-6,7-4294967295\n").unwrap();
+    assert_eq!(1, Day4::part1(&assignments));
+    assert_eq!(1, Day4::part2(&assignments));
+}
+
+/// Every section of a range, so containment and overlap become set tests.
+#[cfg(test)]
+fn sections((start, end): (u32, u32)) -> std::collections::HashSet<u32> {
+    (start..=end).collect()
+}
+
+#[cfg(test)]
+fn reference_contained(a: (u32, u32), b: (u32, u32)) -> bool {
+    let (a, b) = (sections(a), sections(b));
+    a.is_subset(&b) || b.is_subset(&a)
+}
+
+#[cfg(test)]
+fn reference_overlap(a: (u32, u32), b: (u32, u32)) -> bool {
+    !sections(a).is_disjoint(&sections(b))
+}
+
+#[cfg(test)]
+fn range_pairs() -> impl Strategy<Value = Vec<((u32, u32), (u32, u32))>> {
+    let range = (1u32..100, 0u32..20).prop_map(|(start, len)| (start, start + len));
+    prop::collection::vec((range.clone(), range), 0..50)
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn test_matches_reference(pairs in range_pairs()) {
+        let input = pairs
+            .iter()
+            .map(|(a, b)| format!("{}-{},{}-{}\n", a.0, a.1, b.0, b.1))
+            .collect::<String>();
+        let assignments = Day4::parse(&input).unwrap();
+        let contained = pairs.iter().filter(|&&(a, b)| reference_contained(a, b)).count();
+        let overlapping = pairs.iter().filter(|&&(a, b)| reference_overlap(a, b)).count();
+        prop_assert_eq!(contained as u32, Day4::part1(&assignments));
+        prop_assert_eq!(overlapping as u32, Day4::part2(&assignments));
+    }
 }
@@ -1,32 +1,24 @@
-use regex::Regex;
-use std::collections::HashSet;
-use std::os::macos::raw::ino_t;
-use std::str::FromStr;
+use crate::error::ParseError;
+use crate::parser::{self, Line};
+use crate::solution::Solution;
+#[cfg(test)]
+use proptest::prelude::*;
 
-fn count_stacks(line: &str) -> usize {
-    (line.len() + 1) / 4
-}
-
-fn item_on_stack(num: usize, line: &str) -> Option<u8> {
-    let str_pos = num * 4 + 1;
-    let line_bytes = line.as_bytes();
-    if let Some(&ch) = line_bytes.get(str_pos) {
-        if ch >= b'A' && ch <= b'Z' {
-            return Some(ch);
-        }
+/// The crate drawn in a column such as `[D] `, if any.
+fn crate_in(column: &str) -> Option<u8> {
+    match column.as_bytes() {
+        [b'[', ch, ..] if ch.is_ascii_uppercase() => Some(*ch),
+        _ => None,
     }
-    None
 }
 
 fn update_stacks(line: &str, stacks: &mut Vec<Vec<u8>>) {
-    let stack_count = count_stacks(line);
-    while stacks.len() < stack_count {
-        stacks.push(Vec::new())
-    }
-    for i in 0..stack_count {
-        if let Some(ch) = item_on_stack(i, line) {
-            let mut affected_stack = stacks.get_mut(i).unwrap();
-            affected_stack.insert(0, ch)
+    for (i, column) in parser::columns(line, 4).enumerate() {
+        if stacks.len() <= i {
+            stacks.push(Vec::new());
+        }
+        if let Some(ch) = crate_in(column) {
+            stacks[i].insert(0, ch)
         }
     }
 }
@@ -43,104 +35,150 @@ fn get_result(stacks: Vec<Vec<u8>>) -> String {
     result
 }
 
-fn move_crates(count: u32, from_stack: &mut Vec<u8>, to_stack: &mut Vec<u8>) {
-    if count > from_stack.len() as u32 {
-        panic!(
-            "Can't move {} crates from stack with only {} crates.",
-            count,
-            from_stack.len()
-        );
-    }
-    let mut count = count;
-    while count > 0 {
-        to_stack.push(from_stack.pop().unwrap());
-        count -= 1;
-    }
+/// A rearrangement step; stacks are 0-based.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub count: u32,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// The starting stacks, bottom crate first, and the moves to apply to them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Crates {
+    pub stacks: Vec<Vec<u8>>,
+    pub moves: Vec<Move>,
 }
 
-enum ParseState {
-    INITIALIZE_STACK,
-    MOVE_CMD,
+/// Checks that `line` numbers the stacks ` 1   2   3 ...` and returns how
+/// many there are.
+fn parse_stack_numbers(line: &Line) -> Result<usize, ParseError> {
+    let mut count = 0;
+    for (i, column) in parser::columns(line.text, 4).enumerate() {
+        let label = column.trim();
+        if label != (i + 1).to_string() {
+            let message = format!("expected stack number {}", i + 1);
+            return Err(line.error(if label.is_empty() { column } else { label }, &message));
+        }
+        count += 1;
+    }
+    Ok(count)
 }
 
-pub fn solve(input: &str) -> String {
-    let move_ptn = Regex::new("^move (\\d+) from (\\d) to (\\d)$").unwrap();
+/// Parses the drawing of the stacks, ending with the row of stack numbers,
+/// and after a blank line the moves.
+pub fn parse_crates(input: &str) -> Result<Crates, ParseError> {
+    let blocks = parser::blocks(Day5::DAY, input);
+    let mut lines = blocks.iter().flatten();
 
-    let mut stacks = Vec::new();
-    let mut state = ParseState::INITIALIZE_STACK;
-    for line in input.lines() {
-        match state {
-            ParseState::INITIALIZE_STACK => {
-                if line.trim().as_bytes().get(0).unwrap().to_owned() != b'[' {
-                    state = ParseState::MOVE_CMD
-                }
-                update_stacks(line, &mut stacks);
-            }
-            ParseState::MOVE_CMD => {
-                if !move_ptn.is_match(line) {
-                    eprintln!("Unmatched line: {}", line);
-                    continue;
-                }
-                let captures = move_ptn.captures(line).unwrap();
-                let count = captures.get(1).unwrap().as_str();
-                let count = u32::from_str(count).unwrap();
-                let from_stack = usize::from_str(captures.get(2).unwrap().as_str()).unwrap() - 1;
-                let mut from_stack = stacks.get_mut(from_stack).unwrap();
-                let mut workspace = Vec::new();
-                for _ in 0..count {
-                    workspace.insert(0, from_stack.pop().unwrap());
-                }
-                let to_stack = usize::from_str(captures.get(3).unwrap().as_str()).unwrap() - 1;
-                let mut to_stack = stacks.get_mut(to_stack).unwrap();
-                for _ in 0..count {
-                    to_stack.push(workspace.pop().unwrap());
-                }
+    let mut drawing = Vec::new();
+    let numbers = loop {
+        match lines.next() {
+            Some(line) if line.text.trim_start().starts_with('[') => drawing.push(line),
+            Some(line) => break line,
+            None => {
+                let end = input.lines().count() + 1;
+                let message = "missing the row of stack numbers";
+                return Err(ParseError::new(Day5::DAY, end, 1, "", message));
             }
         }
+    };
+    let count = parse_stack_numbers(numbers)?;
+    let mut stacks = vec![Vec::new(); count];
+    for line in drawing {
+        if let Some(extra) = parser::columns(line.text, 4).nth(count) {
+            return Err(line.error(extra, "crate outside the numbered stacks"));
+        }
+        update_stacks(line.text, &mut stacks);
     }
 
-    get_result(stacks)
+    let mut heights = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+    let mut moves = Vec::new();
+    for line in lines {
+        let [count, from, to] = line.pattern(
+            "move {} from {} to {}",
+            "expected 'move <count> from <stack> to <stack>'",
+        )?;
+        let count: u32 = line.int(count, "crate count is too large")?;
+        let stack_index = |text| match line.int::<usize>(text, "no such stack") {
+            Ok(num) if num >= 1 && num <= stacks.len() => Ok(num - 1),
+            _ => Err(line.error(text, "no such stack")),
+        };
+        let from = stack_index(from)?;
+        let to = stack_index(to)?;
+        if count as usize > heights[from] {
+            let message = format!(
+                "cannot move {} crates from a stack of {}",
+                count, heights[from]
+            );
+            return Err(line.error(line.text, &message));
+        }
+        heights[from] -= count as usize;
+        heights[to] += count as usize;
+        moves.push(Move { count, from, to });
+    }
+    crate::debug!("day5: {} stacks, {} moves", stacks.len(), moves.len());
+
+    Ok(Crates { stacks, moves })
 }
 
-pub fn solve2(input: &str) -> String {
-    let move_ptn = Regex::new("^move (\\d+) from (\\d) to (\\d)$").unwrap();
+/// Top crates after moving crates one at a time (CrateMover 9000).
+pub fn move_one_at_a_time(crates: &Crates) -> String {
+    let mut stacks = crates.stacks.clone();
+    for mv in &crates.moves {
+        let from_stack = stacks.get_mut(mv.from).unwrap();
+        let mut workspace = Vec::new();
+        for _ in 0..mv.count {
+            workspace.insert(0, from_stack.pop().unwrap());
+        }
+        let to_stack = stacks.get_mut(mv.to).unwrap();
+        for _ in 0..mv.count {
+            to_stack.push(workspace.pop().unwrap());
+        }
+    }
 
-    let mut stacks = Vec::new();
-    let mut state = ParseState::INITIALIZE_STACK;
-    for line in input.lines() {
-        match state {
-            ParseState::INITIALIZE_STACK => {
-                if line.trim().as_bytes().get(0).unwrap().to_owned() != b'[' {
-                    state = ParseState::MOVE_CMD
-                }
-                update_stacks(line, &mut stacks);
-            }
-            ParseState::MOVE_CMD => {
-                if !move_ptn.is_match(line) {
-                    eprintln!("Unmatched line: {}", line);
-                    continue;
-                }
-                let captures = move_ptn.captures(line).unwrap();
-                let count = captures.get(1).unwrap().as_str();
-                let count = u32::from_str(count).unwrap();
-                let from_stack = usize::from_str(captures.get(2).unwrap().as_str()).unwrap() - 1;
-                let mut from_stack = stacks.get_mut(from_stack).unwrap();
-                let mut workspace = Vec::new();
-                for _ in 0..count {
-                    workspace.push(from_stack.pop().unwrap());
-                }
-                let to_stack = usize::from_str(captures.get(3).unwrap().as_str()).unwrap() - 1;
-                let mut to_stack = stacks.get_mut(to_stack).unwrap();
-                for _ in 0..count {
-                    to_stack.push(workspace.pop().unwrap());
-                }
-            }
+    get_result(stacks)
+}
+
+/// Top crates after moving each batch at once (CrateMover 9001).
+pub fn move_all_at_once(crates: &Crates) -> String {
+    let mut stacks = crates.stacks.clone();
+    for mv in &crates.moves {
+        let from_stack = stacks.get_mut(mv.from).unwrap();
+        let mut workspace = Vec::new();
+        for _ in 0..mv.count {
+            workspace.push(from_stack.pop().unwrap());
+        }
+        let to_stack = stacks.get_mut(mv.to).unwrap();
+        for _ in 0..mv.count {
+            to_stack.push(workspace.pop().unwrap());
         }
     }
 
     get_result(stacks)
 }
 
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = Crates;
+    type Answer = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_crates(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer {
+        move_one_at_a_time(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer {
+        move_all_at_once(input)
+    }
+}
+
 #[test]
 fn test_update_stacks() {
     let input = r#"
@@ -156,63 +194,142 @@ fn test_update_stacks() {
     for line in input.lines() {
         update_stacks(line, &mut stacks);
     }
-    assert_eq!([b'Z', b'N'], stacks.get(0).unwrap().as_slice());
+    assert_eq!([b'Z', b'N'], stacks.first().unwrap().as_slice());
     assert_eq!([b'M', b'C', b'D'], stacks.get(1).unwrap().as_slice());
     assert_eq!([b'P'], stacks.get(2).unwrap().as_slice());
 }
 
 #[test]
-fn test_item_on_stack() {
-    let line = "[D]                     [N] [F]";
-    assert_eq!(Some(b'D'), item_on_stack(0, line));
-    assert_eq!(None, item_on_stack(1, line));
-    assert_eq!(None, item_on_stack(2, line));
-    assert_eq!(None, item_on_stack(3, line));
-    assert_eq!(None, item_on_stack(4, line));
-    assert_eq!(None, item_on_stack(5, line));
-    assert_eq!(Some(b'N'), item_on_stack(6, line));
-    assert_eq!(Some(b'F'), item_on_stack(7, line));
-    assert_eq!(None, item_on_stack(8, line));
+fn test_parse_crates() {
+    let input = "[D]                     [N] [F]\n 1   2   3   4   5   6   7   8   9 \n";
+    let crates = parse_crates(input).unwrap();
+    let mut expected = vec![Vec::new(); 9];
+    expected[0] = vec![b'D'];
+    expected[6] = vec![b'N'];
+    expected[7] = vec![b'F'];
+    assert_eq!(expected, crates.stacks);
+    assert!(crates.moves.is_empty());
 }
 
 #[test]
-fn test_solve() {
-    let input = r#"
-    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
+fn test_parse_error() {
+    let input = "[A] [B]\n 1   2 \n\nmove 1 from 3 to 1\n";
+    let err = Day5::parse(input).err().unwrap();
+    assert_eq!((4, 13, "3"), (err.line, err.column, err.text.as_str()));
 
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2
-"#
-    .strip_prefix("\n")
-    .unwrap()
-    .strip_suffix("\n")
-    .unwrap();
-    let solution = solve(input);
-    assert_eq!("CMZ", solution.as_str());
+    let input = "[A] [B]\n 1   2 \n\nmove 2 from 1 to 2\n";
+    let err = Day5::parse(input).err().unwrap();
+    assert_eq!("cannot move 2 crates from a stack of 1", err.message);
+
+    let input = "[A] [B]\n 1   2 \n\nmove one from 1 to 2\n";
+    let err = Day5::parse(input).err().unwrap();
+    assert_eq!((4, 1), (err.line, err.column));
+
+    // Without the number row, the first move must not be swallowed.
+    let input = "[A] [B]\n\nmove 1 from 1 to 2\n";
+    let err = Day5::parse(input).err().unwrap();
+    assert_eq!((3, 1, "move"), (err.line, err.column, err.text.as_str()));
+    let err = Day5::parse("[A] [B]\n").err().unwrap();
+    assert_eq!(
+        (2, "missing the row of stack numbers"),
+        (err.line, err.message.as_str())
+    );
+
+    let input = "[A] [B]\n 1   3 \n\nmove 1 from 1 to 2\n";
+    let err = Day5::parse(input).err().unwrap();
+    assert_eq!(
+        (2, 6, "expected stack number 2"),
+        (err.line, err.column, err.message.as_str())
+    );
+    let input = "[A] [B] [C]\n 1   2 \n\nmove 1 from 1 to 2\n";
+    let err = Day5::parse(input).err().unwrap();
+    assert_eq!((1, 9, "[C]"), (err.line, err.column, err.text.as_str()));
 }
 
-#[test]
-fn test_solve2() {
-    let input = r#"
-    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
+/// Draws the stacks the way the puzzle does, tallest row first.
+#[cfg(test)]
+fn draw_stacks(stacks: &[Vec<u8>]) -> String {
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut drawing = String::new();
+    for row in (0..height).rev() {
+        let cells = stacks
+            .iter()
+            .map(|stack| match stack.get(row) {
+                Some(&ch) => format!("[{}]", ch as char),
+                None => "   ".to_owned(),
+            })
+            .collect::<Vec<_>>();
+        drawing += &cells.join(" ");
+        drawing.push('\n');
+    }
+    let numbers = (1..=stacks.len())
+        .map(|i| format!(" {} ", i))
+        .collect::<Vec<_>>();
+    drawing + &numbers.join(" ") + "\n"
+}
 
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2
-"#
-    .strip_prefix("\n")
-    .unwrap()
-    .strip_suffix("\n")
-    .unwrap();
-    let solution = solve2(input);
-    assert_eq!("MCD", solution.as_str());
+/// Applies the moves with whole-vector operations; `at_once` keeps the
+/// order of each batch.
+#[cfg(test)]
+fn reference_tops(stacks: &[Vec<u8>], moves: &[Move], at_once: bool) -> String {
+    let mut stacks = stacks.to_vec();
+    for mv in moves {
+        let from = &mut stacks[mv.from];
+        let mut batch = from.split_off(from.len() - mv.count as usize);
+        if !at_once {
+            batch.reverse();
+        }
+        stacks[mv.to].extend(batch);
+    }
+    stacks
+        .iter()
+        .map(|stack| stack.last().map_or(' ', |&ch| ch as char))
+        .collect()
+}
+
+/// Random stacks and moves that never take more crates than a stack holds.
+#[cfg(test)]
+fn crate_layouts() -> impl Strategy<Value = Crates> {
+    let stack = prop::collection::vec(b'A'..=b'Z', 0..8);
+    let stacks = prop::collection::vec(stack, 1..10);
+    let moves = prop::collection::vec((0usize..100, 0usize..9, 0usize..9), 0..30);
+    (stacks, moves).prop_map(|(stacks, seeds)| {
+        let mut heights = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut moves = Vec::new();
+        for (count, from, to) in seeds {
+            let (from, to) = (from % stacks.len(), to % stacks.len());
+            let count = count % (heights[from] + 1);
+            heights[from] -= count;
+            heights[to] += count;
+            moves.push(Move {
+                count: count as u32,
+                from,
+                to,
+            });
+        }
+        Crates { stacks, moves }
+    })
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn test_matches_reference(crates in crate_layouts()) {
+        let moves = crates
+            .moves
+            .iter()
+            .map(|mv| format!("move {} from {} to {}\n", mv.count, mv.from + 1, mv.to + 1))
+            .collect::<String>();
+        let input = draw_stacks(&crates.stacks) + "\n" + &moves;
+        let parsed = Day5::parse(&input).unwrap();
+        prop_assert_eq!(&crates, &parsed);
+        prop_assert_eq!(
+            reference_tops(&crates.stacks, &crates.moves, false),
+            Day5::part1(&parsed)
+        );
+        prop_assert_eq!(
+            reference_tops(&crates.stacks, &crates.moves, true),
+            Day5::part2(&parsed)
+        );
+    }
 }
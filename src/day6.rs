@@ -1,3 +1,9 @@
+use crate::error::ParseError;
+use crate::parser::{self, Line};
+use crate::solution::Solution;
+#[cfg(test)]
+use proptest::prelude::*;
+
 struct FlowCache<const N: usize> {
     bytes: [u8; N],
     cnt: usize,
@@ -37,30 +43,52 @@ impl<const N: usize> FlowCache<N> {
     }
 }
 
-pub fn solve(input: &str) -> u32 {
-    let mut fc: FlowCache<4> = FlowCache::new();
-    let mut cnt = 1;
-    for b in input.bytes() {
+/// The number of characters read when the last `N` are all different, if
+/// that ever happens.
+pub fn find_marker<const N: usize>(input: &str) -> Option<u32> {
+    let mut fc: FlowCache<N> = FlowCache::new();
+    for (cnt, b) in (1..).zip(input.bytes()) {
         fc.push(b);
         if fc.all_unique() {
-            return cnt;
+            return Some(cnt);
         }
-        cnt += 1;
     }
-    panic!("Marker not detected!");
+    None
 }
 
-pub fn solve2(input: &str) -> u32 {
-    let mut fc: FlowCache<14> = FlowCache::new();
-    let mut cnt = 1;
-    for b in input.bytes() {
-        fc.push(b);
-        if fc.all_unique() {
-            return cnt;
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = String;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut lines = parser::lines(Self::DAY, input.trim_end());
+        let datastream = lines.next().unwrap_or(Line::new(Self::DAY, 1, ""));
+        datastream.chars_where(
+            datastream.text,
+            |c| c.is_ascii_lowercase(),
+            "expected a lowercase letter",
+        )?;
+        if let Some(line) = lines.next() {
+            return Err(line.error(line.text, "expected a single line"));
         }
-        cnt += 1;
+        // A start-of-message marker also holds a start-of-packet marker.
+        if find_marker::<14>(datastream.text).is_none() {
+            return Err(datastream.error(datastream.text, "no start-of-message marker"));
+        }
+        Ok(datastream.text.to_owned())
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer {
+        find_marker::<4>(input).expect("checked by parse")
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer {
+        find_marker::<14>(input).expect("checked by parse")
     }
-    panic!("Marker not detected!");
 }
 
 #[test]
@@ -82,18 +110,46 @@ fn test_flow_cache() {
 }
 
 #[test]
-fn test_solve() {
-    assert_eq!(5, solve("bvwbjplbgvbhsrlpgdmjqwftvncz"));
-    assert_eq!(6, solve("nppdvjthqldpwncqszvftbrmjlhg"));
-    assert_eq!(10, solve("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"));
-    assert_eq!(11, solve("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"));
+fn test_parse_error() {
+    let err = Day6::parse("bvwbjp!bgvbh\n").err().unwrap();
+    assert_eq!((1, 7, "!"), (err.line, err.column, err.text.as_str()));
+    assert!(Day6::parse("bvwbjplbgvbh\nabc").is_err());
+    let err = Day6::parse("").err().unwrap();
+    assert_eq!("no start-of-message marker", err.message);
+    assert!(Day6::parse("abcdabcdabcdabcdabcd").is_err());
 }
 
-#[test]
-fn test_solve2() {
-    assert_eq!(19, solve2("mjqjpqmgbljsphdztnvjfqwrcgsmlb"));
-    assert_eq!(23, solve2("bvwbjplbgvbhsrlpgdmjqwftvncz"));
-    assert_eq!(23, solve2("nppdvjthqldpwncqszvftbrmjlhg"));
-    assert_eq!(29, solve2("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"));
-    assert_eq!(26, solve2("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"));
+/// The end of the first window of `n` distinct characters.
+#[cfg(test)]
+fn reference_marker(datastream: &str, n: usize) -> Option<usize> {
+    let bytes = datastream.as_bytes();
+    (n..=bytes.len()).find(|&end| {
+        let window = &bytes[end - n..end];
+        window
+            .iter()
+            .collect::<std::collections::HashSet<_>>()
+            .len()
+            == n
+    })
+}
+
+/// Mostly repetitive datastreams that still end with a start-of-message
+/// marker.
+#[cfg(test)]
+fn datastreams() -> impl Strategy<Value = String> {
+    let noise = prop::collection::vec(prop::sample::select(b"abcdefg".to_vec()), 0..60);
+    let marker = Just(b"hijklmnopqrstu".to_vec()).prop_shuffle();
+    (noise, marker).prop_map(|(noise, marker)| {
+        String::from_utf8(noise.into_iter().chain(marker).collect()).unwrap()
+    })
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn test_matches_reference(datastream in datastreams()) {
+        let parsed = Day6::parse(&datastream).unwrap();
+        prop_assert_eq!(reference_marker(&datastream, 4), Some(Day6::part1(&parsed) as usize));
+        prop_assert_eq!(reference_marker(&datastream, 14), Some(Day6::part2(&parsed) as usize));
+    }
 }
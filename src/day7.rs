@@ -1,275 +1,435 @@
-use std::borrow::{Borrow, BorrowMut};
+use crate::error::ParseError;
+use crate::parser::{self, Line};
+use crate::solution::Solution;
+#[cfg(test)]
+use proptest::prelude::*;
 use std::cell::RefCell;
 use std::collections::{BinaryHeap, HashMap};
 use std::ops::Deref;
-use std::rc::Rc;
-use std::str::FromStr;
+use std::rc::{Rc, Weak};
 
-enum FSNode {
-    DIR {
-        parent: Option<Rc<RefCell<FSNode>>>,
+/// A node of the filesystem rebuilt from a shell transcript. Parents are
+/// held weakly, so dropping the root frees the whole tree.
+pub enum FSNode {
+    Dir {
+        parent: Option<Weak<RefCell<FSNode>>>,
         children: HashMap<String, Rc<RefCell<FSNode>>>,
     },
-    FILE {
-        parent: Rc<RefCell<FSNode>>,
-        size: u32,
+    File {
+        parent: Weak<RefCell<FSNode>>,
+        size: u64,
     },
-    NIL,
 }
 
-impl Default for FSNode {
-    fn default() -> Self {
-        FSNode::NIL
-    }
-}
+/// A directory operation was tried on a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotADirectory;
 
 impl FSNode {
-    fn new_nil() -> Rc<RefCell<Self>> {
-        Rc::new(RefCell::new(FSNode::NIL))
-    }
-
-    fn new_dir(parent: Option<Rc<RefCell<Self>>>) -> Rc<RefCell<Self>> {
-        Rc::new(RefCell::new(FSNode::DIR {
-            parent,
+    pub fn new_dir(parent: Option<Rc<RefCell<Self>>>) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(FSNode::Dir {
+            parent: parent.as_ref().map(Rc::downgrade),
             children: HashMap::new(),
         }))
     }
 
-    fn new_file(parent: Rc<RefCell<FSNode>>, size: u32) -> Rc<RefCell<Self>> {
-        Rc::new(RefCell::new(FSNode::FILE { parent, size }))
+    pub fn new_file(parent: Rc<RefCell<FSNode>>, size: u64) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(FSNode::File {
+            parent: Rc::downgrade(&parent),
+            size,
+        }))
     }
 
-    fn is_dir(&self) -> bool {
-        match self {
-            FSNode::DIR { .. } => true,
-            _ => false,
-        }
+    pub fn is_dir(&self) -> bool {
+        matches!(self, FSNode::Dir { .. })
     }
 
-    fn add_child(&mut self, name: String, fs_node: Rc<RefCell<Self>>) {
+    pub fn add_child(
+        &mut self,
+        name: String,
+        fs_node: Rc<RefCell<Self>>,
+    ) -> Result<(), NotADirectory> {
         match self {
-            FSNode::DIR { children, .. } => {
+            FSNode::Dir { children, .. } => {
                 children.insert(name, fs_node);
+                Ok(())
             }
-            _ => panic!("Cannot add child to a file!"),
-        };
+            FSNode::File { .. } => Err(NotADirectory),
+        }
     }
 
-    fn get_child(&self, name: &str) -> Option<Rc<RefCell<Self>>> {
+    /// The child called `name`; files have no children.
+    pub fn get_child(&self, name: &str) -> Option<Rc<RefCell<Self>>> {
         match self {
-            FSNode::DIR { children, .. } => {
-                if let Some(child) = children.get(name) {
-                    Some(Rc::clone(child))
-                } else {
-                    None
-                }
-            }
-            _ => panic!("Cannot add child to a file!"),
+            FSNode::Dir { children, .. } => children.get(name).map(Rc::clone),
+            FSNode::File { .. } => None,
         }
     }
 
-    fn get_parent(&self) -> Option<Rc<RefCell<Self>>> {
+    pub fn get_parent(&self) -> Option<Rc<RefCell<Self>>> {
         match self {
-            FSNode::DIR {
+            FSNode::Dir {
                 parent: Some(p), ..
             }
-            | FSNode::FILE { parent: p, .. } => Some(Rc::clone(p)),
-            _ => None,
+            | FSNode::File { parent: p, .. } => p.upgrade(),
+            FSNode::Dir { parent: None, .. } => None,
         }
     }
 
-    fn size(&self) -> u32 {
+    /// Total size of a file, or of everything below a directory.
+    pub fn size(&self) -> u64 {
         match self {
-            FSNode::FILE { size, .. } => *size,
-            FSNode::DIR { children, .. } => {
-                let mut sum = 0u32;
+            FSNode::File { size, .. } => *size,
+            FSNode::Dir { children, .. } => {
+                let mut sum = 0u64;
                 for child in children.values() {
                     sum += RefCell::borrow(child).size();
                 }
                 sum
             }
-            FSNode::NIL => {
-                panic!("I shouldn't be here!");
-            }
         }
     }
 
-    fn visit_dirs<F: FnMut(&FSNode)>(&self, visitor: &mut F) {
+    /// Calls `visitor` on every directory, children before their parent.
+    pub fn visit_dirs<F: FnMut(&FSNode)>(&self, visitor: &mut F) {
         match self {
-            FSNode::DIR { children, .. } => {
+            FSNode::Dir { children, .. } => {
                 for child in children.values() {
                     Self::visit_dirs(RefCell::borrow(child).deref(), visitor);
                 }
                 visitor(self);
             }
-            FSNode::FILE { size, .. } => {}
-            FSNode::NIL => {
-                panic!("I shouldn't be here!");
-            }
+            FSNode::File { .. } => {}
         }
     }
 }
 
 enum LineType {
-    CMD_CD_ROOT,
-    CMD_CD_UP,
-    CMD_CD_DOWN { name: String },
-    CMD_LS,
-    RESULT_DIR { name: String },
-    RESULT_FILE { name: String, size: u32 },
+    CdRoot,
+    CdUp,
+    CdDown { name: String },
+    Ls,
+    Dir { name: String },
+    File { name: String, size: u64 },
 }
 
-fn parse_line(line: &str) -> LineType {
-    if line.strip_prefix("$ cd /").is_some() {
-        return LineType::CMD_CD_ROOT;
-    } else if line.strip_prefix("$ cd ..").is_some() {
-        return LineType::CMD_CD_UP;
-    } else if line.strip_prefix("$ ls").is_some() {
-        return LineType::CMD_LS;
-    } else if let Some(dir_name) = line.strip_prefix("$ cd ") {
-        return LineType::CMD_CD_DOWN {
+fn parse_line(line: Line) -> Result<LineType, ParseError> {
+    let text = line.text;
+    let line_type = if text.starts_with("$ cd /") {
+        LineType::CdRoot
+    } else if text.starts_with("$ cd ..") {
+        LineType::CdUp
+    } else if text.starts_with("$ ls") {
+        LineType::Ls
+    } else if let Some(dir_name) = text.strip_prefix("$ cd ") {
+        LineType::CdDown {
             name: dir_name.trim().to_owned(),
-        };
-    } else if let Some(dir_name) = line.strip_prefix("dir ") {
-        return LineType::RESULT_DIR {
+        }
+    } else if let Some(dir_name) = text.strip_prefix("dir ") {
+        LineType::Dir {
             name: dir_name.trim().to_owned(),
-        };
+        }
+    } else if text.starts_with('$') {
+        return Err(line.error(text, "unknown command"));
     } else {
-        let size_name = line.split(" ").collect::<Vec<&str>>();
-        assert_eq!(2, size_name.len());
-        return LineType::RESULT_FILE {
-            name: size_name[1].to_owned(),
-            size: u32::from_str(size_name[0]).unwrap(),
-        };
-    }
-    todo!()
+        let (size, name) =
+            line.split_once(text, " ", "expected '<size> <name>' or 'dir <name>'")?;
+        LineType::File {
+            name: name.to_owned(),
+            size: line.int(size, "expected a file size")?,
+        }
+    };
+    Ok(line_type)
 }
 
-fn build_tree(input: &str) -> Rc<RefCell<FSNode>> {
+/// How deep `cd` may go. Sizes are summed recursively, so this bounds the
+/// stack they need.
+pub const MAX_DEPTH: usize = 1000;
+
+/// Rebuilds the filesystem from a transcript of `cd` and `ls` commands.
+/// Every directory holds at most the sum of all listed file sizes, so
+/// inputs where that sum overflows are rejected.
+pub fn build_tree(input: &str) -> Result<Rc<RefCell<FSNode>>, ParseError> {
     let root = FSNode::new_dir(None);
 
     let mut current_dir = Rc::clone(&root);
-    for line in input.lines() {
-        {
-            let my_dir = RefCell::borrow(&current_dir);
-            if !my_dir.is_dir() {
-                panic!("Current dir is not a dir! {}", line);
-            }
-        }
-
-        let line_type = parse_line(line);
+    let mut depth = 0;
+    let mut total_size = 0u64;
+    for line in parser::lines(Day7::DAY, input) {
+        let line_type = parse_line(line)?;
+        // `cd` only ever enters directories, so this cannot happen.
+        let not_a_dir = |_| line.error(line.text, "not in a directory");
         match line_type {
-            LineType::CMD_CD_ROOT => {
+            LineType::CdRoot => {
                 current_dir = Rc::clone(&root);
+                depth = 0;
             }
-            LineType::CMD_CD_UP => {
-                let mut parent = FSNode::new_nil();
-                {
-                    let my_dir = RefCell::borrow(&current_dir);
-                    parent = my_dir.get_parent().unwrap();
-                }
-                current_dir = Rc::clone(&parent);
+            LineType::CdUp => {
+                let parent = RefCell::borrow(&current_dir).get_parent();
+                current_dir = parent
+                    .ok_or_else(|| line.error(line.text, "cannot leave the root directory"))?;
+                depth -= 1;
             }
-            LineType::CMD_LS => {
+            LineType::Ls => {
                 // NO OP
             }
-            LineType::CMD_CD_DOWN { name } => {
-                let mut next_current = FSNode::new_nil();
-                {
+            LineType::CdDown { name } => {
+                depth += 1;
+                if depth > MAX_DEPTH {
+                    return Err(line.error(line.text, "directories are nested too deeply"));
+                }
+                let next_current = {
                     let mut my_dir = RefCell::borrow_mut(&current_dir);
                     if let Some(node) = my_dir.get_child(&name) {
-                        next_current = Rc::clone(&node);
+                        if !RefCell::borrow(&node).is_dir() {
+                            let name = line.text.trim_end().rsplit(' ').next().unwrap_or("");
+                            return Err(line.error(name, "not a directory"));
+                        }
+                        node
                     } else {
                         let new_child = FSNode::new_dir(Some(current_dir.clone()));
-                        my_dir.add_child(name.clone(), new_child.clone());
-                        next_current = Rc::clone(&new_child);
+                        my_dir
+                            .add_child(name.clone(), new_child.clone())
+                            .map_err(not_a_dir)?;
+                        new_child
                     }
-                }
+                };
                 current_dir = next_current;
             }
-            LineType::RESULT_DIR { name } => {
+            LineType::Dir { name } => {
                 let mut my_dir = RefCell::borrow_mut(&current_dir);
-                my_dir.add_child(name, FSNode::new_dir(Some(Rc::clone(&current_dir))));
+                my_dir
+                    .add_child(name, FSNode::new_dir(Some(Rc::clone(&current_dir))))
+                    .map_err(not_a_dir)?;
             }
-            LineType::RESULT_FILE { name, size } => {
+            LineType::File { name, size } => {
+                total_size = total_size.checked_add(size).ok_or_else(|| {
+                    let size = line.text.split(' ').next().unwrap_or("");
+                    line.error(size, "file sizes add up to too much")
+                })?;
                 let mut my_dir = RefCell::borrow_mut(&current_dir);
-                my_dir.add_child(name, FSNode::new_file(Rc::clone(&current_dir), size));
+                my_dir
+                    .add_child(name, FSNode::new_file(Rc::clone(&current_dir), size))
+                    .map_err(not_a_dir)?;
             }
         }
     }
-    root
+    Ok(root)
 }
 
-pub fn solve(input: &str) -> u32 {
-    let root = build_tree(input);
-
+/// The total size of the directories of at most 100000.
+pub fn sum_small_dirs(root: &Rc<RefCell<FSNode>>) -> u64 {
     let mut sum = 0;
-    let mut print_size = |dir: &FSNode| {
+    let mut add_size = |dir: &FSNode| {
         let size = dir.size();
-        println!("Dir Size: {}", size);
-        if size < 100000 {
+        crate::debug!("day7: dir size {}", size);
+        if size <= 100_000 {
             sum += size;
         }
     };
-    RefCell::borrow(&root).visit_dirs(&mut print_size);
+    RefCell::borrow(root).visit_dirs(&mut add_size);
 
     sum
 }
 
-pub fn solve2(input: &str) -> u32 {
-    let root = build_tree(input);
-
-    let used_space = RefCell::borrow(&root).size();
-    let unused_space = 70_000_000 - used_space;
-    let required_space = 30_000_000;
-    let need_to_free = required_space - unused_space;
+/// The size of the smallest directory whose deletion leaves at least
+/// 30000000 of the 70000000 disk free.
+pub fn smallest_dir_to_free(root: &Rc<RefCell<FSNode>>) -> u64 {
+    let used_space = RefCell::borrow(root).size();
+    let unused_space = 70_000_000u64.saturating_sub(used_space);
+    let required_space = 30_000_000u64;
+    // The root is always a candidate, so the heap is never empty.
+    let need_to_free = required_space.saturating_sub(unused_space);
 
     let mut heap = BinaryHeap::new();
     use std::cmp::Reverse;
 
-    let mut print_size = |dir: &FSNode| {
+    let mut push_candidate = |dir: &FSNode| {
         let size = dir.size();
-        if size > need_to_free {
-            println!("Big Enough: {}", size);
+        if size >= need_to_free {
+            crate::debug!("day7: candidate dir size {}", size);
             heap.push(Reverse(size));
         }
     };
-    RefCell::borrow(&root).visit_dirs(&mut print_size);
+    RefCell::borrow(root).visit_dirs(&mut push_candidate);
+
+    let smallest = heap.pop().unwrap().0;
+    crate::info!(
+        "day7: need to free {}, deleting a dir of size {}",
+        need_to_free,
+        smallest
+    );
+    smallest
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
 
-    heap.pop().unwrap().0
+    type Input = Rc<RefCell<FSNode>>;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        build_tree(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer {
+        sum_small_dirs(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer {
+        smallest_dir_to_free(input)
+    }
 }
 
 #[test]
-fn test_solve() {
-    let input = r#"
-$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k
-"#
-    .strip_prefix("\n")
-    .unwrap()
-    .strip_suffix("\n")
-    .unwrap();
-    assert_eq!(95437, solve(input));
-    assert_eq!(24933642, solve2(input))
+fn test_tree_is_freed() {
+    let root = Day7::parse("$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\n12 b.txt\n").unwrap();
+    let dir = RefCell::borrow(&root).get_child("a").unwrap();
+    drop(root);
+    assert!(RefCell::borrow(&dir).get_parent().is_none());
+}
+
+#[test]
+fn test_files_have_no_children() {
+    let dir = FSNode::new_dir(None);
+    let file = FSNode::new_file(Rc::clone(&dir), 12);
+    let mut file = RefCell::borrow_mut(&file);
+    assert!(file.get_child("a").is_none());
+    assert_eq!(Err(NotADirectory), file.add_child("a".to_owned(), dir));
+    assert_eq!(12, file.size());
+}
+
+#[test]
+fn test_boundaries() {
+    // `a` is exactly 100000, and exactly the space that has to be freed:
+    // both limits are inclusive.
+    let root = Day7::parse("$ cd /\n$ ls\ndir a\n40000000 b\n$ cd a\n$ ls\n100000 c\n").unwrap();
+    assert_eq!(100_000, Day7::part1(&root));
+    assert_eq!(100_000, Day7::part2(&root));
+}
+
+#[test]
+fn test_parse_error() {
+    let err = Day7::parse("$ cd /\n$ ls\nabc b.txt\n").err().unwrap();
+    assert_eq!((3, 1, "abc"), (err.line, err.column, err.text.as_str()));
+    let err = Day7::parse("$ cd /\n$ cd ..\n").err().unwrap();
+    assert_eq!("cannot leave the root directory", err.message);
+    let err = Day7::parse("$ cd /\n$ ls\n12 b.txt\n$ cd b.txt\n")
+        .err()
+        .unwrap();
+    assert_eq!((4, 6, "b.txt"), (err.line, err.column, err.text.as_str()));
+    let err = Day7::parse("$ pwd\n").err().unwrap();
+    assert_eq!("unknown command", err.message);
+
+    let err = Day7::parse("$ cd /\n$ ls\n18446744073709551615 a\n18446744073709551615 b\n")
+        .err()
+        .unwrap();
+    assert_eq!(
+        (4, 1, "file sizes add up to too much"),
+        (err.line, err.column, err.message.as_str())
+    );
+
+    let deep = "$ cd a\n".repeat(MAX_DEPTH);
+    assert!(Day7::parse(&deep).is_ok());
+    let err = Day7::parse(&(deep + "$ cd a\n")).err().unwrap();
+    assert_eq!(MAX_DEPTH + 1, err.line);
+}
+
+/// A generated filesystem: node `i` is `d<i>` or `f<i>`, and its parent is
+/// a directory listed before it, or the root (`None`).
+#[cfg(test)]
+#[derive(Clone, Debug)]
+struct Tree {
+    nodes: Vec<(Option<usize>, Option<u64>)>,
+}
+
+#[cfg(test)]
+impl Tree {
+    fn name(&self, node: usize) -> String {
+        match self.nodes[node].1 {
+            Some(_) => format!("f{}.txt", node),
+            None => format!("d{}", node),
+        }
+    }
+
+    /// Lists `dir`, then visits its subdirectories one by one.
+    fn transcript(&self, dir: Option<usize>, out: &mut String) {
+        let children = (0..self.nodes.len())
+            .filter(|&node| self.nodes[node].0 == dir)
+            .collect::<Vec<_>>();
+        out.push_str("$ ls\n");
+        for &child in &children {
+            match self.nodes[child].1 {
+                Some(size) => *out += &format!("{} {}\n", size, self.name(child)),
+                None => *out += &format!("dir {}\n", self.name(child)),
+            }
+        }
+        for &child in children
+            .iter()
+            .filter(|&&child| self.nodes[child].1.is_none())
+        {
+            *out += &format!("$ cd {}\n", self.name(child));
+            self.transcript(Some(child), out);
+            out.push_str("$ cd ..\n");
+        }
+    }
+
+    /// The size of every directory, the root first, by walking up from each
+    /// file.
+    fn reference_sizes(&self) -> Vec<u64> {
+        let mut sizes = vec![0; self.nodes.len() + 1];
+        for &(parent, size) in &self.nodes {
+            let Some(size) = size else { continue };
+            let mut dir = parent;
+            loop {
+                sizes[dir.map_or(0, |dir| dir + 1)] += size;
+                match dir {
+                    Some(node) => dir = self.nodes[node].0,
+                    None => break,
+                }
+            }
+        }
+        let is_dir = |i: &usize| *i == 0 || self.nodes[i - 1].1.is_none();
+        (0..sizes.len()).filter(is_dir).map(|i| sizes[i]).collect()
+    }
+}
+
+#[cfg(test)]
+fn trees() -> impl Strategy<Value = Tree> {
+    let size = prop_oneof![1u64..50_000, 1u64..20_000_000];
+    let node = (
+        any::<prop::sample::Index>(),
+        prop::option::weighted(0.7, size),
+    );
+    prop::collection::vec(node, 0..40).prop_map(|nodes| {
+        let mut dirs = vec![None];
+        let mut tree = Tree { nodes: Vec::new() };
+        for (i, (parent, size)) in nodes.into_iter().enumerate() {
+            tree.nodes.push((*parent.get(&dirs), size));
+            if size.is_none() {
+                dirs.push(Some(i));
+            }
+        }
+        tree
+    })
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn test_matches_reference(tree in trees()) {
+        let mut input = "$ cd /\n".to_owned();
+        tree.transcript(None, &mut input);
+        let root = Day7::parse(&input).unwrap();
+
+        let sizes = tree.reference_sizes();
+        let small = sizes.iter().filter(|&&size| size <= 100_000).sum::<u64>();
+        let unused = 70_000_000u64.saturating_sub(sizes[0]);
+        let need = 30_000_000u64.saturating_sub(unused);
+        let smallest = sizes.iter().filter(|&&size| size >= need).min().copied();
+        prop_assert_eq!(small, Day7::part1(&root));
+        prop_assert_eq!(smallest, Some(Day7::part2(&root)));
+    }
 }
@@ -1,227 +1,171 @@
-use std::str::FromStr;
-
-struct Map(Vec<Vec<u8>>);
-
-impl Map {
-    fn new(map: Vec<Vec<u8>>) -> Self {
-        Self(map)
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::parser;
+use crate::point::{Direction, Point};
+use crate::solution::Solution;
+#[cfg(test)]
+use proptest::prelude::*;
+
+/// Tree heights.
+pub type Map = Grid<u8>;
+
+/// How many trees can be seen from `tree` looking in `direction`.
+pub fn viewing_distance(map: &Map, tree: Point, direction: Direction) -> u32 {
+    let height = map[tree];
+    let mut count = 0;
+    for (_, &other) in map.ray(tree, direction.step()) {
+        count += 1;
+        if height <= other {
+            break;
+        }
     }
+    count
+}
 
-    fn height(&self) -> i32 {
-        self.0.len() as i32
-    }
+/// The product of the viewing distances, which outgrows a `u32` on maps
+/// wider than a few hundred trees.
+pub fn scenic_score(map: &Map, tree: Point) -> u64 {
+    Direction::ALL
+        .iter()
+        .map(|&direction| viewing_distance(map, tree, direction) as u64)
+        .product()
+}
 
-    fn width(&self) -> i32 {
-        self.0.get(0).unwrap().len() as i32
-    }
+/// Whether `tree` can be seen from the edge of the map in `direction`.
+pub fn visible_from(map: &Map, tree: Point, direction: Direction) -> bool {
+    let height = map[tree];
+    map.ray(tree, direction.step())
+        .all(|(_, &other)| other < height)
+}
 
-    fn get(&self, row_num: i32, col_num: i32) -> Option<u8> {
-        if let Some(row) = self.0.get(row_num as usize) {
-            if let Some(val) = row.get(col_num as usize) {
-                return Some(*val);
-            }
-        }
-        None
-    }
+pub fn is_visible(map: &Map, tree: Point) -> bool {
+    Direction::ALL
+        .iter()
+        .any(|&direction| visible_from(map, tree, direction))
+}
 
-    fn scenic_up(&self, row_num: i32, col_num: i32) -> u32 {
-        let val = self.get(row_num, col_num).unwrap();
-        let mut i = row_num - 1;
-        let j = col_num;
-        let mut count = 0u32;
-        while let Some(cell_val) = self.get(i, j) {
-            count += 1;
-            if val <= cell_val {
-                break;
+pub fn build_map(input: &str) -> Result<Map, ParseError> {
+    let mut width = None;
+    let mut cells = Vec::new();
+    for line in parser::lines(Day8::DAY, input) {
+        let row = line.digits(line.text, "expected a tree height")?;
+        match width {
+            Some(width) if width != row.len() => {
+                let message = format!("expected {} trees in the row, found {}", width, row.len());
+                return Err(line.error(line.text, &message));
             }
-            i -= 1;
+            _ => width = Some(row.len()),
         }
-        count
+        cells.extend(row);
     }
+    Ok(Grid::from_cells(width.unwrap_or(0), cells))
+}
 
-    fn scenic_down(&self, row_num: i32, col_num: i32) -> u32 {
-        let val = self.get(row_num, col_num).unwrap();
-        let mut i = row_num + 1;
-        let j = col_num;
-        let mut count = 0u32;
-        while let Some(cell_val) = self.get(i, j) {
-            count += 1;
-            if val <= cell_val {
-                break;
-            }
-            i += 1;
-        }
-        count
-    }
+pub fn count_visible(map: &Map) -> u32 {
+    map.points().filter(|&tree| is_visible(map, tree)).count() as u32
+}
 
-    fn scenic_left(&self, row_num: i32, col_num: i32) -> u32 {
-        let val = self.get(row_num, col_num).unwrap();
-        let i = row_num;
-        let mut j = col_num - 1;
-        let mut count = 0u32;
-        while let Some(cell_val) = self.get(i, j) {
-            count += 1;
-            if val <= cell_val {
-                break;
-            }
-            j -= 1;
-        }
-        count
-    }
+pub fn max_scenic_score(map: &Map) -> u64 {
+    map.points()
+        .map(|tree| scenic_score(map, tree))
+        .max()
+        .unwrap_or(0)
+}
 
-    fn scenic_right(&self, row_num: i32, col_num: i32) -> u32 {
-        let val = self.get(row_num, col_num).unwrap();
-        let i = row_num;
-        let mut j = col_num + 1;
-        let mut count = 0u32;
-        while let Some(cell_val) = self.get(i, j) {
-            count += 1;
-            if val <= cell_val {
-                break;
-            }
-            j += 1;
-        }
-        count
-    }
+pub struct Day8;
 
-    fn scenic_score(&self, row_num: i32, col_num: i32) -> u32 {
-        self.scenic_up(row_num, col_num)
-            * self.scenic_down(row_num, col_num)
-            * self.scenic_left(row_num, col_num)
-            * self.scenic_right(row_num, col_num)
-    }
+impl Solution for Day8 {
+    const DAY: u8 = 8;
 
-    fn visible_up(&self, row_num: i32, col_num: i32) -> bool {
-        let val = self.get(row_num, col_num).unwrap();
-        let mut i = row_num - 1;
-        let j = col_num;
-        while let Some(cell_val) = self.get(i, j) {
-            if val <= cell_val {
-                return false;
-            }
-            i -= 1;
-        }
-        true
-    }
+    type Input = Map;
+    type Answer = u64;
 
-    fn visible_down(&self, row_num: i32, col_num: i32) -> bool {
-        let val = self.get(row_num, col_num).unwrap();
-        let mut i = row_num + 1;
-        let j = col_num;
-        while let Some(cell_val) = self.get(i, j) {
-            if val <= cell_val {
-                return false;
-            }
-            i += 1;
-        }
-        true
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        build_map(input)
     }
 
-    fn visible_left(&self, row_num: i32, col_num: i32) -> bool {
-        let val = self.get(row_num, col_num).unwrap();
-        let i = row_num;
-        let mut j = col_num - 1;
-        while let Some(cell_val) = self.get(i, j) {
-            if val <= cell_val {
-                return false;
-            }
-            j -= 1;
-        }
-        true
+    fn part1(input: &Self::Input) -> Self::Answer {
+        count_visible(input) as u64
     }
 
-    fn visible_right(&self, row_num: i32, col_num: i32) -> bool {
-        let val = self.get(row_num, col_num).unwrap();
-        let i = row_num;
-        let mut j = col_num + 1;
-        while let Some(cell_val) = self.get(i, j) {
-            if val <= cell_val {
-                return false;
-            }
-            j += 1;
-        }
-        true
+    fn part2(input: &Self::Input) -> Self::Answer {
+        max_scenic_score(input)
     }
 }
 
-fn build_map(input: &str) -> Map {
-    let mut map = Vec::new();
-    for line in input.lines() {
-        let mut row = Vec::new();
-        for char in line.chars() {
-            let val = u8::from_str(&char.to_string()).unwrap();
-            row.push(val);
-        }
-        map.push(row);
-    }
-    Map(map)
+#[test]
+fn test_scenic_score() {
+    let map = build_map("30373\n25512\n65332\n33549\n35390").unwrap();
+    assert_eq!(4, scenic_score(&map, Point::new(2, 1)));
+    assert_eq!(8, scenic_score(&map, Point::new(2, 3)));
+    assert!(is_visible(&map, Point::new(1, 1)));
+    assert!(!is_visible(&map, Point::new(3, 1)));
 }
 
-pub fn solve(input: &str) -> u32 {
-    let mut count = 0;
-    let map = build_map(input);
-    for i in 0..map.height() {
-        let i = i as i32;
-        for j in 0..map.width() {
-            let j = j as i32;
-            if map.visible_up(i, j)
-                || map.visible_down(i, j)
-                || map.visible_left(i, j)
-                || map.visible_right(i, j)
-            {
-                count += 1
-            }
-        }
-    }
+#[test]
+fn test_parse_error() {
+    let err = Day8::parse("30373\n25a12\n").err().unwrap();
+    assert_eq!((2, 3, "a"), (err.line, err.column, err.text.as_str()));
+    let err = Day8::parse("30373\n2551\n").err().unwrap();
+    assert_eq!("expected 5 trees in the row, found 4", err.message);
+}
 
-    count
+#[test]
+fn test_large_scenic_score() {
+    let mut rows = vec!["0".repeat(601); 601];
+    rows[300].replace_range(300..301, "9");
+    let map = build_map(&rows.join("\n")).unwrap();
+    assert_eq!(300u64.pow(4), max_scenic_score(&map));
 }
 
-pub fn solve2(input: &str) -> u32 {
-    let mut max_score = 0;
-
-    let map = build_map(input);
-    for i in 0..map.height() {
-        let i = i as i32;
-        for j in 0..map.width() {
-            let j = j as i32;
-            let score = map.scenic_score(i, j);
-            if score > max_score {
-                max_score = score;
+/// Visible trees and the best scenic score, with plain index loops over
+/// the rows.
+#[cfg(test)]
+fn reference(rows: &[Vec<u8>]) -> (u64, u64) {
+    let (height, width) = (rows.len(), rows[0].len());
+    let (mut visible, mut best) = (0, 0);
+    for y in 0..height {
+        for x in 0..width {
+            let tree = rows[y][x];
+            let lines: [Vec<u8>; 4] = [
+                (0..y).rev().map(|i| rows[i][x]).collect(),
+                (y + 1..height).map(|i| rows[i][x]).collect(),
+                (0..x).rev().map(|i| rows[y][i]).collect(),
+                (x + 1..width).map(|i| rows[y][i]).collect(),
+            ];
+            if lines
+                .iter()
+                .any(|line| line.iter().all(|&other| other < tree))
+            {
+                visible += 1;
+            }
+            let mut score = 1;
+            for line in &lines {
+                let blocked = line.iter().position(|&other| other >= tree);
+                score *= blocked.map_or(line.len(), |i| i + 1) as u64;
             }
+            best = best.max(score);
         }
     }
-    max_score
+    (visible, best)
 }
 
-#[test]
-fn test_solve() {
-    let input = r#"
-30373
-25512
-65332
-33549
-35390
-"#
-    .trim();
-
-    assert_eq!(21, solve(input));
+#[cfg(test)]
+fn forests() -> impl Strategy<Value = Vec<Vec<u8>>> {
+    (1usize..10, 1usize..10).prop_flat_map(|(width, height)| {
+        prop::collection::vec(prop::collection::vec(0u8..10, width), height)
+    })
 }
 
-#[test]
-fn test_solve2() {
-    let input = r#"
-30373
-25512
-65332
-33549
-35390
-"#
-    .trim();
-
-    let map = build_map(input);
-    assert_eq!(4, map.scenic_score(1, 2));
-    assert_eq!(8, map.scenic_score(3, 2));
-
-    assert_eq!(8, solve2(input));
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn test_matches_reference(rows in forests()) {
+        let input = rows
+            .iter()
+            .map(|row| row.iter().map(|height| (b'0' + height) as char).collect::<String>() + "\n")
+            .collect::<String>();
+        let map = Day8::parse(&input).unwrap();
+        prop_assert_eq!(reference(&rows), (Day8::part1(&map), Day8::part2(&map)));
+    }
 }
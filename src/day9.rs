@@ -1,187 +1,208 @@
+use crate::error::ParseError;
+use crate::parser::{self, Line};
+use crate::point::{Direction, Point};
+use crate::solution::Solution;
+#[cfg(test)]
+use proptest::prelude::*;
 use std::collections::HashSet;
-use std::mem;
-use std::ops::Deref;
-use std::str::FromStr;
+use std::num::NonZeroUsize;
 
-struct Map {
-    knot_pos: Vec<(i32, i32)>,
+/// A rope of knots, head first.
+pub struct Map {
+    knot_pos: Vec<Point>,
 }
 
-fn reconcile(head_pos: &(i32, i32), tail_pos: &(i32, i32)) -> (i32, i32) {
-    let mut new_tail_pos = tail_pos.clone();
-    let delta_x = head_pos.0 - tail_pos.0;
-    let delta_y = head_pos.1 - tail_pos.1;
-    if delta_x.abs() > 1 {
-        new_tail_pos.0 += delta_x.signum();
-        if delta_y.abs() > 0 {
-            new_tail_pos.1 += delta_y.signum();
-        }
-    } else if delta_y.abs() > 1 {
-        new_tail_pos.1 += delta_y.signum();
-        if delta_x.abs() > 0 {
-            new_tail_pos.0 += delta_x.signum();
-        }
+/// Where the tail moves to keep up with the head.
+pub fn reconcile(head_pos: Point, tail_pos: Point) -> Point {
+    if head_pos.chebyshev(tail_pos) > 1 {
+        tail_pos + (head_pos - tail_pos).signum()
+    } else {
+        tail_pos
     }
-    new_tail_pos
 }
 
 impl Map {
-    fn new(knot_count: u32) -> Self {
-        let mut knot_pos = Vec::new();
-        for _ in 0..knot_count {
-            knot_pos.push((0, 0));
+    /// A rope of `knot_count` knots, all at the origin.
+    pub fn new(knot_count: NonZeroUsize) -> Self {
+        Map {
+            knot_pos: vec![Point::ORIGIN; knot_count.get()],
         }
-        Map { knot_pos }
     }
 
-    fn reconcile(&mut self) -> (i32, i32) {
-        let mut new_tail_pos = (0, 0);
-        for i in 0..(self.knot_pos.len() - 1) {
-            let head_pos = self.knot_pos[i];
-            let tail_pos = self.knot_pos[i + 1];
-            new_tail_pos = reconcile(&head_pos, &tail_pos);
-            let _ = mem::replace(&mut self.knot_pos[i + 1], new_tail_pos);
-        }
-        new_tail_pos
+    pub fn knots(&self) -> &[Point] {
+        &self.knot_pos
     }
 
-    fn move_up(&mut self) -> (i32, i32) {
-        let head_pos = self.knot_pos[0];
-        let new_head_pos = (head_pos.0, head_pos.1 + 1);
-        let _ = mem::replace(&mut self.knot_pos[0], new_head_pos);
-        self.reconcile()
-    }
-    fn move_down(&mut self) -> (i32, i32) {
-        let head_pos = self.knot_pos[0];
-        let new_head_pos = (head_pos.0, head_pos.1 - 1);
-        let _ = mem::replace(&mut self.knot_pos[0], new_head_pos);
-        self.reconcile()
-    }
-    fn move_left(&mut self) -> (i32, i32) {
-        let head_pos = self.knot_pos[0];
-        let new_head_pos = (head_pos.0 - 1, head_pos.1);
-        let _ = mem::replace(&mut self.knot_pos[0], new_head_pos);
-        self.reconcile()
+    /// Pulls each knot after the head along; returns the new tail position.
+    pub fn reconcile(&mut self) -> Point {
+        for i in 1..self.knot_pos.len() {
+            self.knot_pos[i] = reconcile(self.knot_pos[i - 1], self.knot_pos[i]);
+        }
+        self.knot_pos[self.knot_pos.len() - 1]
     }
-    fn move_right(&mut self) -> (i32, i32) {
-        let head_pos = self.knot_pos[0];
-        let new_head_pos = (head_pos.0 + 1, head_pos.1);
-        let _ = mem::replace(&mut self.knot_pos[0], new_head_pos);
+
+    /// Moves the head one step; returns the new tail position.
+    pub fn move_head(&mut self, direction: Direction) -> Point {
+        self.knot_pos[0] += direction.step();
         self.reconcile()
     }
 }
 
-struct MapTracker {
+/// A rope that records every position its tail visits.
+pub struct MapTracker {
     map: Map,
-    tail_pos_set: HashSet<(i32, i32)>,
+    tail_pos_set: HashSet<Point>,
 }
 
 impl MapTracker {
-    fn new(knot_count: u32) -> Self {
-        let mut tail_pos_set = HashSet::new();
-        tail_pos_set.insert((0, 0));
+    pub fn new(knot_count: NonZeroUsize) -> Self {
         MapTracker {
             map: Map::new(knot_count),
-            tail_pos_set,
+            tail_pos_set: HashSet::from([Point::ORIGIN]),
         }
     }
 
-    fn move_up(&mut self, num: u32) {
-        for _ in 0..num {
-            self.tail_pos_set.insert(self.map.move_up());
-        }
+    pub fn map(&self) -> &Map {
+        &self.map
     }
 
-    fn move_down(&mut self, num: u32) {
-        for _ in 0..num {
-            self.tail_pos_set.insert(self.map.move_down());
-        }
+    pub fn tail_positions(&self) -> &HashSet<Point> {
+        &self.tail_pos_set
     }
 
-    fn move_left(&mut self, num: u32) {
+    pub fn move_head(&mut self, direction: Direction, num: u32) {
         for _ in 0..num {
-            self.tail_pos_set.insert(self.map.move_left());
+            self.tail_pos_set.insert(self.map.move_head(direction));
         }
     }
+}
 
-    fn move_right(&mut self, num: u32) {
-        for _ in 0..num {
-            self.tail_pos_set.insert(self.map.move_right());
-        }
-    }
+pub type Motion = (Direction, u32);
+
+pub fn parse_motion(line: Line) -> Result<Motion, ParseError> {
+    let (direction, num) = line.split_once(line.text, " ", "expected '<direction> <steps>'")?;
+    let num = line.int(num, "expected a step count")?;
+    let direction = match direction {
+        "U" => Direction::Up,
+        "D" => Direction::Down,
+        "L" => Direction::Left,
+        "R" => Direction::Right,
+        _ => return Err(line.error(direction, "expected U, D, L or R")),
+    };
+    Ok((direction, num))
 }
 
-pub fn solve(input: &str) -> u32 {
-    let mut map_tracker = MapTracker::new(2);
-    for line in input.lines() {
-        let line_split = line.split(" ").collect::<Vec<&str>>();
-        assert_eq!(2, line_split.len());
-        let direction = line_split.get(0).unwrap().deref();
-        let num = u32::from_str(line_split.get(1).unwrap()).unwrap();
-        match direction {
-            "U" => map_tracker.move_up(num),
-            "D" => map_tracker.move_down(num),
-            "L" => map_tracker.move_left(num),
-            "R" => map_tracker.move_right(num),
-            _ => panic!("Unexpected direction: '{}'", direction),
-        }
+pub fn count_tail_positions(motions: &[Motion], knot_count: NonZeroUsize) -> u32 {
+    let mut map_tracker = MapTracker::new(knot_count);
+    for &(direction, num) in motions {
+        map_tracker.move_head(direction, num);
     }
-    map_tracker.tail_pos_set.len() as u32
+    map_tracker.tail_positions().len() as u32
 }
 
-pub fn solve2(input: &str) -> u32 {
-    let mut map_tracker = MapTracker::new(10);
-    for line in input.lines() {
-        let line_split = line.split(" ").collect::<Vec<&str>>();
-        assert_eq!(2, line_split.len());
-        let direction = line_split.get(0).unwrap().deref();
-        let num = u32::from_str(line_split.get(1).unwrap()).unwrap();
-        match direction {
-            "U" => map_tracker.move_up(num),
-            "D" => map_tracker.move_down(num),
-            "L" => map_tracker.move_left(num),
-            "R" => map_tracker.move_right(num),
-            _ => panic!("Unexpected direction: '{}'", direction),
-        }
+/// The ropes of part 1 and part 2.
+const SHORT_ROPE: NonZeroUsize = NonZeroUsize::new(2).unwrap();
+const LONG_ROPE: NonZeroUsize = NonZeroUsize::new(10).unwrap();
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<Motion>;
+    type Answer = u32;
+
+    /// Rejects motions adding up to more steps than a coordinate can hold.
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut total = 0u32;
+        parser::each_line(Self::DAY, input, |line| {
+            let motion = parse_motion(line)?;
+            total = total
+                .checked_add(motion.1)
+                .filter(|&total| total <= i32::MAX as u32)
+                .ok_or_else(|| line.error(line.text, "too many steps in total"))?;
+            Ok(motion)
+        })
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer {
+        count_tail_positions(input, SHORT_ROPE)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer {
+        count_tail_positions(input, LONG_ROPE)
     }
-    map_tracker.tail_pos_set.len() as u32
 }
 
 #[test]
-fn test_solve() {
-    let input = r#"
-R 4
-U 4
-"#
-    .trim();
-    assert_eq!(7, solve(input));
-
-    let input = r#"
-R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2
-"#
-    .trim();
-    assert_eq!(13, solve(input));
+fn test_reconcile() {
+    let tail = Point::new(1, 1);
+    assert_eq!(tail, reconcile(Point::new(2, 2), tail));
+    assert_eq!(Point::new(2, 1), reconcile(Point::new(3, 1), tail));
+    assert_eq!(Point::new(2, 2), reconcile(Point::new(3, 2), tail));
+    assert_eq!(Point::new(0, 0), reconcile(Point::new(-1, -1), tail));
+    // A single knot is its own tail.
+    let motions = [(Direction::Right, 3)];
+    assert_eq!(4, count_tail_positions(&motions, NonZeroUsize::MIN));
 }
 
 #[test]
-fn test_solve2() {
-    let input = r#"
-R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20
-"#
-    .trim();
-    assert_eq!(36, solve2(input));
+fn test_parse_error() {
+    let err = Day9::parse("R 4\nX 4\n").err().unwrap();
+    assert_eq!((2, 1, "X"), (err.line, err.column, err.text.as_str()));
+    let err = Day9::parse("R 4\nU -4\n").err().unwrap();
+    assert_eq!((2, 3, "-4"), (err.line, err.column, err.text.as_str()));
+    let err = Day9::parse("R 2000000000\nL 2000000000\n").err().unwrap();
+    assert_eq!(
+        (2, "too many steps in total"),
+        (err.line, err.message.as_str())
+    );
+}
+
+/// Counts tail positions one step at a time with plain coordinates.
+#[cfg(test)]
+fn reference_tail_positions(moves: &[(char, u32)], knot_count: usize) -> usize {
+    let mut knots = vec![(0i32, 0i32); knot_count];
+    let mut visited = std::collections::BTreeSet::from([(0, 0)]);
+    for &(direction, steps) in moves {
+        for _ in 0..steps {
+            match direction {
+                'U' => knots[0].1 -= 1,
+                'D' => knots[0].1 += 1,
+                'L' => knots[0].0 -= 1,
+                _ => knots[0].0 += 1,
+            }
+            for i in 1..knot_count {
+                let (dx, dy) = (knots[i - 1].0 - knots[i].0, knots[i - 1].1 - knots[i].1);
+                if dx.abs() > 1 || dy.abs() > 1 {
+                    knots[i].0 += dx.signum();
+                    knots[i].1 += dy.signum();
+                }
+            }
+            visited.insert(knots[knot_count - 1]);
+        }
+    }
+    visited.len()
+}
+
+#[cfg(test)]
+fn moves() -> impl Strategy<Value = Vec<(char, u32)>> {
+    prop::collection::vec(
+        (prop::sample::select(vec!['U', 'D', 'L', 'R']), 1u32..20),
+        0..60,
+    )
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn test_matches_reference(moves in moves()) {
+        let input = moves
+            .iter()
+            .map(|(direction, steps)| format!("{} {}\n", direction, steps))
+            .collect::<String>();
+        let motions = Day9::parse(&input).unwrap();
+        prop_assert_eq!(reference_tail_positions(&moves, 2) as u32, Day9::part1(&motions));
+        prop_assert_eq!(reference_tail_positions(&moves, 10) as u32, Day9::part2(&motions));
+    }
 }
@@ -0,0 +1,105 @@
+use crate::answers::AnswersError;
+use crate::input::InputError;
+use std::fmt;
+
+/// A malformed puzzle input. Lines and columns are 1-based.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(day: u8, line: usize, column: usize, text: &str, message: &str) -> Self {
+        ParseError {
+            day,
+            line,
+            column,
+            text: text.to_owned(),
+            message: message.to_owned(),
+        }
+    }
+
+    /// Reports `text`, which must be a slice of `line`, at its own column.
+    pub fn at(day: u8, line_number: usize, line: &str, text: &str, message: &str) -> Self {
+        ParseError::new(day, line_number, column_of(line, text), text, message)
+    }
+}
+
+fn column_of(line: &str, text: &str) -> usize {
+    let line_start = line.as_ptr() as usize;
+    let text_start = text.as_ptr() as usize;
+    if text_start < line_start || text_start > line_start + line.len() {
+        return 1;
+    }
+    line[..text_start - line_start].chars().count() + 1
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: {}: '{}'",
+            self.day, self.line, self.column, self.message, self.text
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Input(InputError),
+    Parse(ParseError),
+    Answers(AnswersError),
+    /// A solver panicked; the panic was caught so other days could go on.
+    Panic {
+        day: u8,
+        message: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Input(err) => err.fmt(f),
+            Error::Parse(err) => err.fmt(f),
+            Error::Answers(err) => err.fmt(f),
+            Error::Panic { day, message } => write!(f, "day {} panicked: {}", day, message),
+        }
+    }
+}
+
+impl From<InputError> for Error {
+    fn from(err: InputError) -> Self {
+        Error::Input(err)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+impl From<AnswersError> for Error {
+    fn from(err: AnswersError) -> Self {
+        Error::Answers(err)
+    }
+}
+
+#[test]
+fn test_column_of() {
+    let line = "2-4,x-8";
+    let err = ParseError::at(4, 3, line, &line[4..5], "expected a number");
+    assert_eq!(5, err.column);
+    assert_eq!("x", err.text);
+    assert_eq!(
+        "day 4, line 3, column 5: expected a number: 'x'",
+        err.to_string()
+    );
+
+    let err = ParseError::at(4, 3, line, "elsewhere", "expected a number");
+    assert_eq!(1, err.column);
+}
@@ -0,0 +1,106 @@
+use crate::answers;
+use crate::registry;
+use crate::verify::{self, Check};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const DEFAULT_DIR: &str = "examples";
+
+/// A sample input from the puzzle text, stored as `dayN.txt` or
+/// `dayN-<name>.txt` next to a `.toml` file with its expected answers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example {
+    pub day: u8,
+    pub name: String,
+    pub input: PathBuf,
+    pub answers: PathBuf,
+}
+
+/// The day number in an example name such as `day9` or `day9-larger`.
+fn day_of(name: &str) -> Option<u8> {
+    let rest = name.strip_prefix("day")?;
+    let number = rest.split_once('-').map_or(rest, |(number, _)| number);
+    u8::from_str(number).ok()
+}
+
+/// Finds every example in `dir`, ordered by day and then by name.
+pub fn discover(dir: &Path) -> io::Result<Vec<Example>> {
+    let mut examples = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let input = entry?.path();
+        if input.extension().is_none_or(|ext| ext != "txt") {
+            continue;
+        }
+        let Some(name) = input.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let Some(day) = day_of(name) else {
+            continue;
+        };
+        examples.push(Example {
+            day,
+            name: name.to_owned(),
+            answers: input.with_extension("toml"),
+            input,
+        });
+    }
+    examples.sort_by(|a, b| (a.day, &a.name).cmp(&(b.day, &b.name)));
+    Ok(examples)
+}
+
+impl Example {
+    /// Solves both parts of the example and compares them with its answers.
+    pub fn check(&self) -> Vec<Check> {
+        let Some(day) = registry::find(self.day) else {
+            return verify::errors(self.day, &format!("day {} is not implemented", self.day));
+        };
+        let answers = match answers::load_file(self.day, self.answers.clone()) {
+            Ok(answers) => answers,
+            Err(err) => return verify::errors(self.day, &err),
+        };
+        let input = match fs::read_to_string(&self.input) {
+            Ok(input) => input,
+            Err(err) => return verify::errors(self.day, &err),
+        };
+        verify::check(&day, &input, &answers).unwrap_or_else(|err| verify::errors(self.day, &err))
+    }
+}
+
+#[test]
+fn test_day_of() {
+    assert_eq!(Some(9), day_of("day9"));
+    assert_eq!(Some(12), day_of("day12-larger"));
+    assert_eq!(None, day_of("dayX"));
+    assert_eq!(None, day_of("README"));
+}
+
+#[test]
+fn test_examples() {
+    use crate::verify::Status;
+
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_DIR);
+    let examples = discover(&dir).unwrap();
+    for day in registry::days() {
+        assert!(
+            examples.iter().any(|example| example.day == day.number),
+            "no example for day {}",
+            day.number
+        );
+    }
+    let mut failures = Vec::new();
+    for example in &examples {
+        // The main example of a day must have answers for both parts.
+        let main = example.name == format!("day{}", example.day);
+        for check in example.check() {
+            if check.status.is_regression() || (main && check.status == Status::Missing) {
+                failures.push(format!(
+                    "{} {:?}: {}",
+                    example.name, check.part, check.status
+                ));
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,128 @@
+#[cfg(test)]
+use crate::http::FakeHttp;
+use crate::http::{self, Http, HttpError};
+use crate::input;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// No session cookie is configured.
+    NoSession,
+    Http(HttpError),
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::NoSession => write!(
+                f,
+                "no session cookie: set {} or write it to {}",
+                http::SESSION_VAR,
+                http::SESSION_FILE
+            ),
+            FetchError::Http(err) => err.fmt(f),
+            FetchError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl From<HttpError> for FetchError {
+    fn from(err: HttpError) -> Self {
+        FetchError::Http(err)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already on disk; nothing was requested.
+    Cached,
+    Downloaded,
+}
+
+pub fn input_url(day: u8) -> String {
+    format!("{}/day/{}/input", http::BASE_URL, day)
+}
+
+/// Whether `dir` already holds the input for `day`. An empty file is a
+/// placeholder, not a cached input.
+pub fn is_cached(dir: &Path, day: u8) -> bool {
+    fs::metadata(input::day_file(dir, day)).is_ok_and(|meta| meta.len() > 0)
+}
+
+/// Downloads the input for `day` into `dir` unless it is already cached.
+pub fn fetch_input<H: Http + ?Sized>(
+    http: &H,
+    session: Option<&str>,
+    dir: &Path,
+    day: u8,
+) -> Result<Fetched, FetchError> {
+    if is_cached(dir, day) {
+        return Ok(Fetched::Cached);
+    }
+    let session = session.ok_or(FetchError::NoSession)?;
+    let input = http.get(&input_url(day), session)?;
+    if input.is_empty() {
+        return Err(FetchError::Http(HttpError {
+            url: input_url(day),
+            message: "empty response".to_owned(),
+        }));
+    }
+
+    let path = input::day_file(dir, day);
+    let io_error = |path: &Path| {
+        let path = path.to_owned();
+        move |error| FetchError::Io { path, error }
+    };
+    fs::create_dir_all(dir).map_err(io_error(dir))?;
+    // Write then rename, so an interrupted download never looks cached.
+    let partial = path.with_extension("txt.part");
+    fs::write(&partial, input).map_err(io_error(&partial))?;
+    fs::rename(&partial, &path).map_err(io_error(&path))?;
+    Ok(Fetched::Downloaded)
+}
+
+#[test]
+fn test_fetch_input() {
+    let dir = std::env::temp_dir().join(format!("aoc22-fetch-{}", std::process::id()));
+    let http = FakeHttp::new(Ok("R 4\nU 4\n"));
+
+    assert!(matches!(
+        fetch_input(&http, None, &dir, 9),
+        Err(FetchError::NoSession)
+    ));
+    assert_eq!(
+        Fetched::Downloaded,
+        fetch_input(&http, Some("abc"), &dir, 9).unwrap()
+    );
+    assert_eq!(
+        vec!["GET https://adventofcode.com/2022/day/9/input abc"],
+        *http.requests.borrow()
+    );
+    assert_eq!(
+        "R 4\nU 4\n",
+        fs::read_to_string(input::day_file(&dir, 9)).unwrap()
+    );
+
+    // A cached input is never requested again, even without a session.
+    assert_eq!(Fetched::Cached, fetch_input(&http, None, &dir, 9).unwrap());
+    assert_eq!(1, http.requests.borrow().len());
+
+    // An empty placeholder is replaced; a failed download leaves it alone.
+    fs::write(input::day_file(&dir, 3), "").unwrap();
+    let failing = FakeHttp::new(Err("404 Not Found"));
+    assert!(fetch_input(&failing, Some("abc"), &dir, 3).is_err());
+    assert!(!is_cached(&dir, 3));
+    assert_eq!(
+        Fetched::Downloaded,
+        fetch_input(&http, Some("abc"), &dir, 3).unwrap()
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,185 @@
+use crate::point::{Direction, Point, Vec2};
+use std::ops::{Index, IndexMut};
+
+/// Steps to the four orthogonal neighbours: up, down, left and right.
+pub const ORTHOGONAL: [Vec2; 4] = [
+    Direction::Up.step(),
+    Direction::Down.step(),
+    Direction::Left.step(),
+    Direction::Right.step(),
+];
+
+/// Steps to all eight neighbours, including the diagonals.
+pub const ADJACENT: [Vec2; 8] = [
+    Point::new(-1, -1),
+    Point::new(0, -1),
+    Point::new(1, -1),
+    Point::new(-1, 0),
+    Point::new(1, 0),
+    Point::new(-1, 1),
+    Point::new(0, 1),
+    Point::new(1, 1),
+];
+
+/// A rectangular grid stored row by row in a single `Vec`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from cells in row order; `cells.len()` must be a
+    /// multiple of `width`.
+    pub fn from_cells(width: usize, cells: Vec<T>) -> Self {
+        let height = cells.len().checked_div(width).unwrap_or(0);
+        assert_eq!(width * height, cells.len(), "ragged grid");
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (0..self.width as i32).contains(&point.x) && (0..self.height as i32).contains(&point.y)
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then(|| point.y as usize * self.width + point.x as usize)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    /// Every position, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// Every position with its cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    /// The cells of row `y`, or `None` if `y` is outside the grid.
+    pub fn row(&self, y: usize) -> Option<impl Iterator<Item = &T>> {
+        (y < self.height).then(|| self.cells[y * self.width..(y + 1) * self.width].iter())
+    }
+
+    /// The cells of column `x`, or `None` if `x` is outside the grid.
+    pub fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+        (x < self.width).then(|| self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    /// The cells reached by repeatedly adding `step` to `from`, excluding
+    /// `from` itself and stopping at the edge of the grid. A zero `step`
+    /// never reaches an edge, so it gives an empty ray.
+    pub fn ray(&self, from: Point, step: Vec2) -> impl Iterator<Item = (Point, &T)> {
+        let mut point = from;
+        std::iter::from_fn(move || {
+            if step == Point::ORIGIN {
+                return None;
+            }
+            point += step;
+            self.get(point).map(|cell| (point, cell))
+        })
+    }
+
+    /// The in-bounds neighbours of `point` in the given directions.
+    fn neighbours<'a>(
+        &'a self,
+        point: Point,
+        steps: &'a [Vec2],
+    ) -> impl Iterator<Item = Point> + 'a {
+        steps
+            .iter()
+            .map(move |&step| point + step)
+            .filter(|&neighbour| self.contains(neighbour))
+    }
+
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, &ORTHOGONAL)
+    }
+
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, &ADJACENT)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point).expect("point outside the grid")
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point).expect("point outside the grid")
+    }
+}
+
+#[test]
+fn test_access() {
+    let mut grid = Grid::from_cells(3, vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!((3, 2), (grid.width(), grid.height()));
+    assert_eq!(Some(&6), grid.get(Point::new(2, 1)));
+    assert_eq!(None, grid.get(Point::new(3, 0)));
+    assert_eq!(None, grid.get(Point::new(-1, 0)));
+    assert_eq!(None, grid.get(Point::new(0, 2)));
+    grid[Point::new(1, 0)] = 9;
+    assert_eq!(vec![&1, &9, &3], grid.row(0).unwrap().collect::<Vec<_>>());
+    assert_eq!(vec![&9, &5], grid.column(1).unwrap().collect::<Vec<_>>());
+    assert!(grid.row(2).is_none());
+    assert!(grid.column(3).is_none());
+    assert_eq!(6, grid.iter().count());
+    assert_eq!(Some((Point::new(2, 1), &6)), grid.iter().last());
+}
+
+#[test]
+fn test_ray() {
+    let grid = Grid::from_cells(3, (0..9).collect());
+    let ray = grid.ray(Point::new(0, 0), Point::new(1, 1));
+    assert_eq!(vec![4, 8], ray.map(|(_, &cell)| cell).collect::<Vec<_>>());
+    assert_eq!(0, grid.ray(Point::new(1, 0), Point::new(0, -1)).count());
+    assert_eq!(0, grid.ray(Point::new(1, 1), Point::ORIGIN).count());
+}
+
+#[test]
+fn test_neighbours() {
+    let grid = Grid::new(3, 3, ());
+    assert_eq!(2, grid.neighbours4(Point::new(0, 0)).count());
+    assert_eq!(4, grid.neighbours4(Point::new(1, 1)).count());
+    assert_eq!(3, grid.neighbours8(Point::new(2, 2)).count());
+    assert_eq!(8, grid.neighbours8(Point::new(1, 1)).count());
+    let empty = Grid::<u8>::from_cells(0, Vec::new());
+    assert_eq!(0, empty.points().count());
+}
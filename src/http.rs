@@ -0,0 +1,160 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+pub const BASE_URL: &str = "https://adventofcode.com/2022";
+/// Environment variable holding the adventofcode.com session cookie.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// File read for the session cookie when the variable is not set.
+pub const SESSION_FILE: &str = ".session";
+
+const USER_AGENT: &str = "aoc22 (Advent of Code 2022 solutions)";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpError {
+    pub url: String,
+    pub message: String,
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.url, self.message)
+    }
+}
+
+/// The requests the CLI makes to adventofcode.com, authenticated with a
+/// session cookie. Tests substitute an in-memory fake.
+pub trait Http {
+    fn get(&self, url: &str, session: &str) -> Result<String, HttpError>;
+
+    fn post_form(
+        &self,
+        url: &str,
+        session: &str,
+        form: &[(&str, &str)],
+    ) -> Result<String, HttpError>;
+}
+
+/// Talks to the network by running `curl`. The session cookie is passed on
+/// stdin so it never shows up in the process list.
+pub struct Curl;
+
+impl Curl {
+    fn request(&self, url: &str, session: &str, args: &[&str]) -> Result<String, HttpError> {
+        let error = |message: String| HttpError {
+            url: url.to_owned(),
+            message,
+        };
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--config", "-"])
+            .args(args)
+            .arg(url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| error(format!("cannot run curl: {}", err)))?;
+        let config = format!(
+            "header = \"Cookie: session={}\"\nuser-agent = \"{}\"\n",
+            session, USER_AGENT
+        );
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(config.as_bytes())
+            .map_err(|err| error(format!("cannot configure curl: {}", err)))?;
+        let output = child
+            .wait_with_output()
+            .map_err(|err| error(format!("curl failed: {}", err)))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(error(stderr.trim().to_owned()));
+        }
+        String::from_utf8(output.stdout).map_err(|_| error("response is not UTF-8".to_owned()))
+    }
+}
+
+impl Http for Curl {
+    fn get(&self, url: &str, session: &str) -> Result<String, HttpError> {
+        self.request(url, session, &[])
+    }
+
+    fn post_form(
+        &self,
+        url: &str,
+        session: &str,
+        form: &[(&str, &str)],
+    ) -> Result<String, HttpError> {
+        let fields = form
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>();
+        let mut args = Vec::new();
+        for field in &fields {
+            args.extend(["--data-urlencode", field.as_str()]);
+        }
+        self.request(url, session, &args)
+    }
+}
+
+/// The session cookie from [`SESSION_VAR`] or, failing that, [`SESSION_FILE`].
+pub fn load_session() -> Option<String> {
+    let session = env::var(SESSION_VAR)
+        .ok()
+        .or_else(|| fs::read_to_string(SESSION_FILE).ok())?;
+    let session = session.trim();
+    (!session.is_empty() && !session.contains(['"', '\n'])).then(|| session.to_owned())
+}
+
+/// Answers every request with the same response and records what was asked.
+#[cfg(test)]
+pub(crate) struct FakeHttp {
+    response: Result<String, String>,
+    pub requests: std::cell::RefCell<Vec<String>>,
+}
+
+#[cfg(test)]
+impl FakeHttp {
+    pub fn new(response: Result<&str, &str>) -> Self {
+        FakeHttp {
+            response: response.map(str::to_owned).map_err(str::to_owned),
+            requests: Default::default(),
+        }
+    }
+
+    fn respond(&self, url: &str) -> Result<String, HttpError> {
+        self.response.clone().map_err(|message| HttpError {
+            url: url.to_owned(),
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+impl Http for FakeHttp {
+    fn get(&self, url: &str, session: &str) -> Result<String, HttpError> {
+        self.requests
+            .borrow_mut()
+            .push(format!("GET {} {}", url, session));
+        self.respond(url)
+    }
+
+    fn post_form(
+        &self,
+        url: &str,
+        session: &str,
+        form: &[(&str, &str)],
+    ) -> Result<String, HttpError> {
+        let fields = form
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>();
+        self.requests
+            .borrow_mut()
+            .push(format!("POST {} {} {}", url, session, fields.join("&")));
+        self.respond(url)
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_DIR: &str = "inputs";
+
+/// Where a day's puzzle input is read from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    /// A single file, used as-is for whichever day is run.
+    File(PathBuf),
+    Stdin,
+    /// A directory holding one `dayN.txt` per day.
+    Dir(PathBuf),
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::Dir(PathBuf::from(DEFAULT_DIR))
+    }
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    pub source_name: String,
+    pub error: io::Error,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Cannot read input from {}: {}",
+            self.source_name, self.error
+        )
+    }
+}
+
+pub fn day_file(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{}.txt", day))
+}
+
+/// FNV-1a hash of the input text, for telling inputs apart in reports.
+pub fn hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|error| InputError {
+        source_name: path.display().to_string(),
+        error,
+    })
+}
+
+impl InputSource {
+    /// `-` selects stdin, anything else is a file path.
+    pub fn from_arg(arg: &str) -> Self {
+        if arg == "-" {
+            InputSource::Stdin
+        } else {
+            InputSource::File(PathBuf::from(arg))
+        }
+    }
+
+    /// Whether this source can supply input for more than one day.
+    pub fn is_per_day(&self) -> bool {
+        matches!(self, InputSource::Dir(_))
+    }
+
+    pub fn load(&self, day: u8) -> Result<String, InputError> {
+        match self {
+            InputSource::File(path) => read_file(path),
+            InputSource::Dir(dir) => read_file(&day_file(dir, day)),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|error| InputError {
+                        source_name: "stdin".to_owned(),
+                        error,
+                    })?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_load_from_dir() {
+    let dir = std::env::temp_dir().join(format!("aoc22-input-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(day_file(&dir, 3), "abc\n").unwrap();
+
+    let source = InputSource::Dir(dir.clone());
+    assert_eq!("abc\n", source.load(3).unwrap());
+    let err = source.load(4).unwrap_err();
+    assert_eq!(day_file(&dir, 4).display().to_string(), err.source_name);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_from_arg() {
+    assert_eq!(InputSource::Stdin, InputSource::from_arg("-"));
+    assert_eq!(
+        InputSource::File(PathBuf::from("inputs/day1.txt")),
+        InputSource::from_arg("inputs/day1.txt")
+    );
+}
+
+#[test]
+fn test_hash() {
+    assert_eq!(0xcbf29ce484222325, hash(""));
+    assert_eq!(0xaf63dc4c8601ec8c, hash("a"));
+    assert_ne!(hash("R 4\n"), hash("R 5\n"));
+}
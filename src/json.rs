@@ -0,0 +1,108 @@
+//! Just enough JSON writing for the runner's machine-readable output.
+
+use std::fmt::{self, Write};
+
+pub fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A JSON array of already-valid JSON values, such as numbers.
+pub fn array<T: fmt::Display>(values: &[T]) -> String {
+    let values = values.iter().map(T::to_string).collect::<Vec<_>>();
+    format!("[{}]", values.join(","))
+}
+
+/// A JSON object whose fields are written in insertion order.
+#[derive(Clone, Debug, Default)]
+pub struct Object {
+    fields: Vec<(String, String)>,
+}
+
+impl Object {
+    pub fn new() -> Self {
+        Object::default()
+    }
+
+    /// Adds a field whose value is already valid JSON.
+    pub fn raw(mut self, key: &str, value: String) -> Self {
+        self.fields.push((key.to_owned(), value));
+        self
+    }
+
+    pub fn str(self, key: &str, value: &str) -> Self {
+        self.raw(key, quote(value))
+    }
+
+    pub fn num<N: Into<u128>>(self, key: &str, value: N) -> Self {
+        self.raw(key, value.into().to_string())
+    }
+
+    /// Adds a float, or `null` if it is not finite.
+    pub fn float(self, key: &str, value: f64) -> Self {
+        if value.is_finite() {
+            self.raw(key, value.to_string())
+        } else {
+            self.null(key)
+        }
+    }
+
+    pub fn null(self, key: &str) -> Self {
+        self.raw(key, "null".to_owned())
+    }
+
+    pub fn object(self, key: &str, value: Object) -> Self {
+        self.raw(key, value.to_string())
+    }
+}
+
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('{')?;
+        for (i, (key, value)) in self.fields.iter().enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+            write!(f, "{}:{}", quote(key), value)?;
+        }
+        f.write_char('}')
+    }
+}
+
+#[test]
+fn test_quote() {
+    assert_eq!(r#""plain""#, quote("plain"));
+    assert_eq!(r#""a \"b\" \\ c\n\u0001""#, quote("a \"b\" \\ c\n\u{1}"));
+}
+
+#[test]
+fn test_object() {
+    let inner = Object::new().str("kind", "parse");
+    let object = Object::new()
+        .num("day", 5u8)
+        .str("answer", "CMZ")
+        .null("elapsed_ns")
+        .object("error", inner)
+        .float("mean", 2.5)
+        .float("nan", f64::NAN)
+        .raw("counts", array(&[1, 2]));
+    assert_eq!(
+        r#"{"day":5,"answer":"CMZ","elapsed_ns":null,"error":{"kind":"parse"},"mean":2.5,"nan":null,"counts":[1,2]}"#,
+        object.to_string()
+    );
+}
@@ -0,0 +1,32 @@
+//! Advent of Code 2022 solutions. Each `dayN` module implements
+//! [`solution::Solution`] and is listed in [`registry::days`].
+
+pub mod answers;
+pub mod bench;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod error;
+pub mod examples;
+pub mod fetch;
+pub mod grid;
+pub mod http;
+pub mod input;
+pub mod json;
+pub mod log;
+pub mod parser;
+pub mod point;
+pub mod registry;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod stats;
+pub mod submit;
+pub mod verify;
+pub mod watch;
@@ -0,0 +1,64 @@
+//! Solver diagnostics, written to stderr and silent unless verbosity is raised.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Quiet,
+    Info,
+    Debug,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Quiet as u8);
+
+impl Level {
+    /// Maps the number of `-v` flags to a level.
+    pub fn from_verbosity(count: u8) -> Self {
+        match count {
+            0 => Level::Quiet,
+            1 => Level::Info,
+            _ => Level::Debug,
+        }
+    }
+}
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn level() -> Level {
+    Level::from_verbosity(LEVEL.load(Ordering::Relaxed))
+}
+
+pub fn enabled(level: Level) -> bool {
+    level <= self::level()
+}
+
+/// Logs a message shown with `-v` and above.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Info) {
+            eprintln!("[info] {}", format_args!($($arg)*));
+        }
+    };
+}
+
+/// Logs a message shown with `-vv`.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            eprintln!("[debug] {}", format_args!($($arg)*));
+        }
+    };
+}
+
+#[test]
+fn test_from_verbosity() {
+    assert_eq!(Level::Quiet, Level::from_verbosity(0));
+    assert_eq!(Level::Info, Level::from_verbosity(1));
+    assert_eq!(Level::Debug, Level::from_verbosity(2));
+    assert_eq!(Level::Debug, Level::from_verbosity(5));
+    assert!(Level::Info < Level::Debug);
+}
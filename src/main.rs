@@ -1,44 +1,807 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-
-fn main() {
-    //println!("Day1: {}", day1::solve());
-    //println!("Day1-2: {}", day1::solve2());
-    //println!("Day2: {}", day2::solve());
-    //println!("Day2-2: {}", day2::solve2());
-
-    //let day3_input = include_str!("../inputs/day3.txt");
-    //println!("Day3: {}", day3::solve(day3_input));
-    //println!("Day3-2: {}", day3::solve2(day3_input));
-
-    //let day4_input = include_str!("../inputs/day4.txt");
-    //println!("Day4: {}", day4::solve(day4_input));
-    //println!("Day4-2: {}", day4::solve2(day4_input));
-
-    //let day5_input = include_str!("../inputs/day5.txt");
-    //println!("Day5: {}", day5::solve(day5_input));
-    //println!("Day5-2: {}", day5::solve2(day5_input));
-
-    //let day6_input = include_str!("../inputs/day6.txt");
-    //println!("Day6: {}", day6::solve(day6_input));
-    //println!("Day6-2: {}", day6::solve2(day6_input));
-
-    // let day7_input = include_str!("../inputs/day7.txt");
-    // println!("Day7: {}", day7::solve(day7_input));
-    // println!("Day7-2: {}", day7::solve2(day7_input));
-
-    //let day8_input = include_str!("../inputs/day8.txt");
-    //println!("Day8: {}", day8::solve(day8_input));
-    //println!("Day8-2: {}", day8::solve2(day8_input));
-
-    let day9_input = include_str!("../inputs/day9.txt");
-    println!("Day9: {}", day9::solve(day9_input));
-    println!("Day9-2: {}", day9::solve2(day9_input));
+use aoc22::answers;
+use aoc22::bench;
+use aoc22::day1::{self, Day1};
+use aoc22::day2::{self, Day2, Rules};
+use aoc22::error::Error;
+use aoc22::fetch::{self, Fetched};
+use aoc22::http::{self, Curl};
+use aoc22::input::{self, InputSource};
+use aoc22::log;
+use aoc22::registry;
+use aoc22::runner;
+use aoc22::scaffold;
+use aoc22::solution::{Day, Part, Solution};
+use aoc22::stats;
+use aoc22::submit::{self, Verdict};
+use aoc22::verify::{self, Status};
+use aoc22::watch::{self, Watcher};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{self, ExitCode};
+use std::str::FromStr;
+use std::thread;
+
+const USAGE: &str = "Usage:
+  aoc22 run <day|all> [--part <1|2>] [--input <file|->] [--inputs-dir <dir>]
+            [--format <text|json>] [--threads <n>]
+  aoc22 verify [day|all] [--inputs-dir <dir>] [--answers-dir <dir>]
+  aoc22 bench [day|all] [--iterations <n>] [--inputs-dir <dir>]
+  aoc22 fetch <day|all> [--inputs-dir <dir>]
+  aoc22 submit <day> <1|2> [--input <file|->] [--inputs-dir <dir>] [--answers-dir <dir>]
+  aoc22 new <day>
+  aoc22 watch <day> [--once] [--inputs-dir <dir>]
+  aoc22 inventory [--input <file|->] [--inputs-dir <dir>] [--format <text|csv|json>]
+                  [--bins <n>]
+  aoc22 score [--rules <file>] [--input <file|->] [--inputs-dir <dir>]
+
+run all solves the days in parallel on --threads workers (default: one per
+CPU) and prints a summary table. All commands accept -v (info) or -vv (debug)
+to print solver diagnostics.
+fetch and submit read the adventofcode.com session cookie from $AOC_SESSION
+or .session. submit records every attempt in <answers-dir>/submissions.tsv.
+new scaffolds src/day<N>.rs, registers it and creates its input and example
+files; run it from the project root. watch rebuilds and re-runs a day's
+examples and input whenever src/day<N>.rs or its input changes; --once runs
+a single round against the previous one. inventory prints the count, total,
+mean, median, percentiles and a histogram of up to --bins bins (default 5,
+at most 1000) of the calories each elf in the day 1 input carries. score
+scores the day 2 strategy guide both ways under the game in a --rules file
+(default: rock paper scissors), compares it with optimal play and ranks every
+other reading of its second column as shapes; see rules/ for examples.";
+
+enum DaySelection {
+    All,
+    One(u8),
+}
+
+impl DaySelection {
+    fn days(&self) -> Vec<Day> {
+        match self {
+            DaySelection::All => registry::days(),
+            DaySelection::One(day) => vec![registry::find(*day).unwrap()],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum Format {
+    #[default]
+    Text,
+    /// One JSON object per day and part, one per line.
+    Json,
+    /// Only for inventory.
+    Csv,
+}
+
+enum Command {
+    Run {
+        days: DaySelection,
+        part: Option<Part>,
+        input: InputSource,
+        format: Format,
+        threads: usize,
+    },
+    Verify {
+        days: DaySelection,
+        input: InputSource,
+        answers_dir: PathBuf,
+    },
+    Bench {
+        days: DaySelection,
+        input: InputSource,
+        iterations: usize,
+    },
+    Fetch {
+        days: Vec<u8>,
+        inputs_dir: PathBuf,
+    },
+    Submit {
+        day: Day,
+        part: Part,
+        input: InputSource,
+        answers_dir: PathBuf,
+    },
+    New {
+        day: u8,
+    },
+    Watch {
+        day: u8,
+        inputs_dir: PathBuf,
+        once: bool,
+    },
+    Inventory {
+        input: InputSource,
+        format: Format,
+        bins: usize,
+    },
+    Score {
+        input: InputSource,
+        rules: Option<PathBuf>,
+    },
+}
+
+#[derive(Default)]
+struct Options {
+    part: Option<Part>,
+    input: InputSource,
+    answers_dir: Option<PathBuf>,
+    iterations: Option<usize>,
+    threads: Option<usize>,
+    bins: Option<usize>,
+    rules: Option<PathBuf>,
+    format: Option<Format>,
+    once: bool,
+    verbosity: u8,
+}
+
+fn parse_day(arg: &str) -> Result<DaySelection, String> {
+    if arg == "all" {
+        return Ok(DaySelection::All);
+    }
+    let day = u8::from_str(arg).map_err(|_| format!("Invalid day: '{}'", arg))?;
+    if registry::find(day).is_none() {
+        let days = registry::days();
+        return Err(format!(
+            "Unknown day: {} (implemented days: {}-{})",
+            day,
+            days[0].number,
+            days[days.len() - 1].number
+        ));
+    }
+    Ok(DaySelection::One(day))
+}
+
+/// Any puzzle day, implemented or not.
+fn parse_puzzle_day(arg: &str) -> Result<u8, String> {
+    match u8::from_str(arg) {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!("Invalid day: '{}' (expected 1-25)", arg)),
+    }
+}
+
+fn parse_part(arg: &str) -> Result<Part, String> {
+    match arg {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(format!("Unknown part: '{}' (expected 1 or 2)", arg)),
+    }
+}
+
+fn parse_format(arg: &str) -> Result<Format, String> {
+    match arg {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        _ => Err(format!(
+            "Unknown format: '{}' (expected text, json or csv)",
+            arg
+        )),
+    }
+}
+
+/// Splits the arguments after the command into positional arguments and the
+/// options around them.
+fn parse_rest<'a>(
+    mut args: impl Iterator<Item = &'a String>,
+) -> Result<(Vec<&'a str>, Options), String> {
+    let mut positional = Vec::new();
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--part" | "-p" => options.part = Some(parse_part(value()?)?),
+            "--input" | "-i" => options.input = InputSource::from_arg(value()?),
+            "--inputs-dir" => options.input = InputSource::Dir(PathBuf::from(value()?)),
+            "--once" => options.once = true,
+            "-v" | "--verbose" => options.verbosity += 1,
+            "-vv" => options.verbosity += 2,
+            "--format" => options.format = Some(parse_format(value()?)?),
+            "--rules" => options.rules = Some(PathBuf::from(value()?)),
+            "--answers-dir" => options.answers_dir = Some(PathBuf::from(value()?)),
+            "--iterations" | "-n" => {
+                let value = value()?;
+                match usize::from_str(value) {
+                    Ok(n) if n > 0 => options.iterations = Some(n),
+                    _ => return Err(format!("Invalid iteration count: '{}'", value)),
+                }
+            }
+            "--threads" | "-j" => {
+                let value = value()?;
+                match usize::from_str(value) {
+                    Ok(n) if n > 0 => options.threads = Some(n),
+                    _ => return Err(format!("Invalid thread count: '{}'", value)),
+                }
+            }
+            "--bins" => {
+                let value = value()?;
+                match usize::from_str(value) {
+                    Ok(n) if (1..=stats::MAX_BINS).contains(&n) => options.bins = Some(n),
+                    _ => return Err(format!("Invalid bin count: '{}'", value)),
+                }
+            }
+            _ if !arg.starts_with('-') => positional.push(arg.as_str()),
+            _ => return Err(format!("Unexpected argument: '{}'", arg)),
+        }
+    }
+    Ok((positional, options))
+}
+
+/// At most one positional argument, a day selection.
+fn parse_days(positional: &[&str]) -> Result<Option<DaySelection>, String> {
+    match positional {
+        [] => Ok(None),
+        [day] => parse_day(day).map(Some),
+        [_, extra, ..] => Err(format!("Unexpected argument: '{}'", extra)),
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut args = args.iter();
+    let command = args.next().map(String::as_str);
+    let (positional, options) = parse_rest(args)?;
+    log::set_level(log::Level::from_verbosity(options.verbosity));
+    if options.once && command != Some("watch") {
+        return Err("--once is only used by watch".to_owned());
+    }
+    if options.threads.is_some() && command != Some("run") {
+        return Err("--threads is only used by run".to_owned());
+    }
+    if options.bins.is_some() && command != Some("inventory") {
+        return Err("--bins is only used by inventory".to_owned());
+    }
+    if options.rules.is_some() && command != Some("score") {
+        return Err("--rules is only used by score".to_owned());
+    }
+    if options.format == Some(Format::Csv) && command != Some("inventory") {
+        return Err("--format csv is only used by inventory".to_owned());
+    }
+    match command {
+        Some("run") => {
+            let days = parse_days(&positional)?;
+            let days = days.ok_or_else(|| "Missing day".to_owned())?;
+            if options.answers_dir.is_some() {
+                return Err("--answers-dir is only used by verify".to_owned());
+            }
+            if options.iterations.is_some() {
+                return Err("--iterations is only used by bench".to_owned());
+            }
+            if matches!(days, DaySelection::All) && !options.input.is_per_day() {
+                return Err("--input can only be used with a single day".to_owned());
+            }
+            Ok(Command::Run {
+                days,
+                part: options.part,
+                input: options.input,
+                format: options.format.unwrap_or_default(),
+                threads: options.threads.unwrap_or_else(runner::default_threads),
+            })
+        }
+        Some("verify") => {
+            let days = parse_days(&positional)?;
+            if options.format.is_some() {
+                return Err("--format is only used by run and inventory".to_owned());
+            }
+            if options.part.is_some() {
+                return Err("verify always checks both parts".to_owned());
+            }
+            if options.iterations.is_some() {
+                return Err("--iterations is only used by bench".to_owned());
+            }
+            if !options.input.is_per_day() {
+                return Err("verify reads inputs from --inputs-dir".to_owned());
+            }
+            Ok(Command::Verify {
+                days: days.unwrap_or(DaySelection::All),
+                input: options.input,
+                answers_dir: options
+                    .answers_dir
+                    .unwrap_or_else(|| PathBuf::from(answers::DEFAULT_DIR)),
+            })
+        }
+        Some("bench") => {
+            let days = parse_days(&positional)?;
+            if options.format.is_some() {
+                return Err("--format is only used by run and inventory".to_owned());
+            }
+            if options.part.is_some() {
+                return Err("bench always times both parts".to_owned());
+            }
+            if options.answers_dir.is_some() {
+                return Err("--answers-dir is only used by verify".to_owned());
+            }
+            let days = days.unwrap_or(DaySelection::All);
+            if matches!(days, DaySelection::All) && !options.input.is_per_day() {
+                return Err("--input can only be used with a single day".to_owned());
+            }
+            Ok(Command::Bench {
+                days,
+                input: options.input,
+                iterations: options.iterations.unwrap_or(bench::DEFAULT_ITERATIONS),
+            })
+        }
+        Some("fetch") => {
+            let days = match positional.as_slice() {
+                ["all"] => registry::days().iter().map(|day| day.number).collect(),
+                [day] => vec![parse_puzzle_day(day)?],
+                [] => return Err("Missing day".to_owned()),
+                [_, extra, ..] => return Err(format!("Unexpected argument: '{}'", extra)),
+            };
+            if options.part.is_some() || options.format.is_some() {
+                return Err("fetch only takes a day and --inputs-dir".to_owned());
+            }
+            if options.answers_dir.is_some() || options.iterations.is_some() {
+                return Err("fetch only takes a day and --inputs-dir".to_owned());
+            }
+            let InputSource::Dir(inputs_dir) = options.input else {
+                return Err("fetch writes inputs to --inputs-dir".to_owned());
+            };
+            Ok(Command::Fetch { days, inputs_dir })
+        }
+        Some("submit") => {
+            let (day, part) = match positional.as_slice() {
+                [day, part] => (parse_day(day)?, parse_part(part)?),
+                [_] | [] => return Err("Missing day or part".to_owned()),
+                [_, _, extra, ..] => return Err(format!("Unexpected argument: '{}'", extra)),
+            };
+            let DaySelection::One(day) = day else {
+                return Err("submit takes a single day".to_owned());
+            };
+            if options.part.is_some() || options.format.is_some() || options.iterations.is_some() {
+                return Err("submit takes the part as its second argument".to_owned());
+            }
+            Ok(Command::Submit {
+                day: registry::find(day).unwrap(),
+                part,
+                input: options.input,
+                answers_dir: options
+                    .answers_dir
+                    .unwrap_or_else(|| PathBuf::from(answers::DEFAULT_DIR)),
+            })
+        }
+        Some("new") => {
+            let day = match positional.as_slice() {
+                [day] => parse_puzzle_day(day)?,
+                [] => return Err("Missing day".to_owned()),
+                [_, extra, ..] => return Err(format!("Unexpected argument: '{}'", extra)),
+            };
+            if options.part.is_some() || options.format.is_some() || options.iterations.is_some() {
+                return Err("new only takes a day".to_owned());
+            }
+            let default_input = Path::new(input::DEFAULT_DIR);
+            if options.answers_dir.is_some()
+                || !matches!(&options.input, InputSource::Dir(dir) if dir == default_input)
+            {
+                return Err("new only takes a day".to_owned());
+            }
+            if registry::find(day).is_some() {
+                return Err(format!("Day {} is already implemented", day));
+            }
+            Ok(Command::New { day })
+        }
+        Some("watch") => {
+            let day = match positional.as_slice() {
+                [day] => parse_puzzle_day(day)?,
+                [] => return Err("Missing day".to_owned()),
+                [_, extra, ..] => return Err(format!("Unexpected argument: '{}'", extra)),
+            };
+            if options.part.is_some() || options.format.is_some() || options.iterations.is_some() {
+                return Err("watch always runs both parts".to_owned());
+            }
+            if options.answers_dir.is_some() {
+                return Err("--answers-dir is only used by verify".to_owned());
+            }
+            let InputSource::Dir(inputs_dir) = options.input else {
+                return Err("watch reads the input from --inputs-dir".to_owned());
+            };
+            // The watcher itself may predate the day; each round is checked
+            // by a freshly built binary.
+            if options.once && registry::find(day).is_none() {
+                return Err(format!("Unknown day: {}", day));
+            }
+            Ok(Command::Watch {
+                day,
+                inputs_dir,
+                once: options.once,
+            })
+        }
+        Some("inventory") => {
+            if let Some(extra) = positional.first() {
+                return Err(format!("Unexpected argument: '{}'", extra));
+            }
+            if options.part.is_some() || options.iterations.is_some() {
+                return Err("inventory only reads the day 1 input".to_owned());
+            }
+            if options.answers_dir.is_some() {
+                return Err("--answers-dir is only used by verify".to_owned());
+            }
+            Ok(Command::Inventory {
+                input: options.input,
+                format: options.format.unwrap_or_default(),
+                bins: options.bins.unwrap_or(stats::DEFAULT_BINS),
+            })
+        }
+        Some("score") => {
+            if let Some(extra) = positional.first() {
+                return Err(format!("Unexpected argument: '{}'", extra));
+            }
+            if options.part.is_some() || options.format.is_some() || options.iterations.is_some() {
+                return Err("score only reads the day 2 input and --rules".to_owned());
+            }
+            if options.answers_dir.is_some() {
+                return Err("--answers-dir is only used by verify".to_owned());
+            }
+            Ok(Command::Score {
+                input: options.input,
+                rules: options.rules,
+            })
+        }
+        Some(cmd) => Err(format!("Unknown command: '{}'", cmd)),
+        None => Err("Missing command".to_owned()),
+    }
+}
+
+fn label(day: u8, part: Part) -> String {
+    match part {
+        Part::One => format!("Day{}", day),
+        Part::Two => format!("Day{}-2", day),
+    }
+}
+
+/// Runs every selected day, reporting errors without stopping; returns false
+/// if any day failed. Days run in parallel, and `run all` ends with a summary
+/// table rather than one line per answer.
+fn run(
+    days: DaySelection,
+    part: Option<Part>,
+    input: &InputSource,
+    format: Format,
+    threads: usize,
+) -> bool {
+    let parts = match part {
+        Some(part) => vec![part],
+        None => Part::BOTH.to_vec(),
+    };
+    let summary = runner::run_all(&days.days(), input, &parts, threads);
+    match format {
+        Format::Json => {
+            for object in summary.runs.iter().flat_map(|run| run.to_json()) {
+                println!("{}", object);
+            }
+        }
+        Format::Text if matches!(days, DaySelection::All) => print!("{}", summary.table()),
+        Format::Text | Format::Csv => {
+            for run in &summary.runs {
+                match &run.result {
+                    Ok(part_runs) => {
+                        for part_run in part_runs {
+                            println!("{}: {}", label(run.day, part_run.part), part_run.answer);
+                        }
+                    }
+                    Err(err) => eprintln!("error: {}", err),
+                }
+            }
+        }
+    }
+    summary.runs.iter().all(|run| run.result.is_ok())
+}
+
+/// Prints statistics on every elf's items in the day 1 input.
+fn inventory(input: &InputSource, format: Format, bins: usize) -> Result<(), Error> {
+    let input = input.load(Day1::DAY)?;
+    let inventory = day1::inventory(&input, bins)?;
+    match format {
+        Format::Text => print!("{}", stats::report(&inventory, "elf")),
+        Format::Csv => print!("{}", stats::csv(&inventory, "elf")),
+        Format::Json => {
+            for object in stats::to_json(&inventory, "elf") {
+                println!("{}", object);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Scores the day 2 guide under the given rules, reading its second column
+/// both as shapes and as outcomes, against optimal play and under every
+/// other reading of the column as shapes.
+fn score(input: &InputSource, rules: Option<&Path>) -> Result<(), Error> {
+    let rules = match rules {
+        Some(path) => Rules::parse(&InputSource::File(path.to_owned()).load(Day2::DAY)?)?,
+        None => Rules::standard(),
+    };
+    let guide = rules.parse_guide(&input.load(Day2::DAY)?)?;
+    println!("shapes: {}", rules.names().join(" "));
+    let analysis = guide.analyze();
+    println!("by play: {}", analysis.by_play);
+    println!("by outcome: {}", analysis.by_outcome);
+    println!(
+        "optimal: {} (by play -{}, by outcome -{})",
+        analysis.optimal,
+        analysis.optimal - analysis.by_play,
+        analysis.optimal - analysis.by_outcome
+    );
+    match guide.decodings() {
+        Some(decodings) => {
+            println!("decodings:");
+            for (decoding, score) in decodings {
+                println!("  {}  {}", rules.describe(&decoding), score);
+            }
+        }
+        None => println!(
+            "decodings: skipped for more than {} shapes",
+            day2::MAX_DECODING_SHAPES
+        ),
+    }
+    Ok(())
+}
+
+/// Prints one line per check and a summary; returns false on any regression.
+fn verify(days: DaySelection, input: &InputSource, answers_dir: &Path) -> bool {
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for day in days.days() {
+        for check in verify::verify_day(&day, input, answers_dir) {
+            let answer = check.answer.as_deref().unwrap_or("-");
+            println!(
+                "{}: {} {}",
+                label(check.day, check.part),
+                answer,
+                check.status
+            );
+            if check.status.is_regression() {
+                failed += 1;
+            } else if check.status == Status::Missing {
+                missing += 1;
+            } else {
+                passed += 1;
+            }
+        }
+    }
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    failed == 0
+}
+
+fn bench(days: DaySelection, input: &InputSource, iterations: usize) -> Result<(), Error> {
+    let mut timings = Vec::new();
+    for day in days.days() {
+        let input = input.load(day.number)?;
+        timings.push(bench::bench_day(&day, &input, iterations)?);
+    }
+    print!("{}", bench::report(&timings));
+    Ok(())
+}
+
+/// Downloads every input that is not cached yet; returns false on failure.
+fn fetch(days: &[u8], inputs_dir: &Path) -> bool {
+    let session = http::load_session();
+    let mut ok = true;
+    for &day in days {
+        let path = input::day_file(inputs_dir, day);
+        match fetch::fetch_input(&Curl, session.as_deref(), inputs_dir, day) {
+            Ok(Fetched::Cached) => println!("Day{}: cached at {}", day, path.display()),
+            Ok(Fetched::Downloaded) => println!("Day{}: saved to {}", day, path.display()),
+            Err(err) => {
+                eprintln!("error: day {}: {}", day, err);
+                ok = false;
+            }
+        }
+    }
+    ok
+}
+
+/// Solves one part and submits the answer; returns false unless it is correct.
+fn submit(day: &Day, part: Part, input: &InputSource, answers_dir: &Path) -> bool {
+    let answer = match runner::run_day(day, input, &[part]).result {
+        Ok(part_runs) => part_runs[0].answer.clone(),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return false;
+        }
+    };
+    let history = answers_dir.join(submit::HISTORY_FILE);
+    let session = http::load_session();
+    let verdict = submit::submit(
+        &Curl,
+        session.as_deref(),
+        &history,
+        day.number,
+        part,
+        &answer,
+        submit::now(),
+    );
+    match verdict {
+        Ok(verdict) => {
+            println!("{}: {} {}", label(day.number, part), answer, verdict);
+            verdict == Verdict::Correct
+        }
+        Err(err) => {
+            eprintln!("error: {}: {}", label(day.number, part), err);
+            false
+        }
+    }
+}
+
+/// Scaffolds a new day in the current directory and lists what changed.
+fn new_day(day: u8) -> bool {
+    match scaffold::new_day(Path::new("."), day) {
+        Ok(scaffold) => {
+            for path in &scaffold.created {
+                println!("created {}", path.display());
+            }
+            for path in &scaffold.updated {
+                println!("updated {}", path.display());
+            }
+            true
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            false
+        }
+    }
+}
+
+/// Runs one round of `watch` and compares it with the round before.
+fn watch_once(day: u8, inputs_dir: &Path) -> bool {
+    let day = registry::find(day).unwrap();
+    let state = watch::state_file(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("target"),
+        day.number,
+    );
+    let inputs = InputSource::Dir(inputs_dir.to_owned());
+    match watch::run_once(&day, &inputs, &state) {
+        Ok(report) => {
+            print!("{}", report);
+            true
+        }
+        Err(err) => {
+            eprintln!("error: {}: {}", state.display(), err);
+            false
+        }
+    }
+}
+
+/// Rebuilds the crate and runs `watch --once` in the new binary, so that
+/// edits to the day's source take effect.
+fn rebuild_and_run(day: u8, inputs_dir: &Path) {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let mut cargo = process::Command::new(env::var_os("CARGO").unwrap_or("cargo".into()));
+    cargo
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(&manifest);
+    if !cfg!(debug_assertions) {
+        cargo.arg("--release");
+    }
+    cargo
+        .args(["--", "watch", &day.to_string(), "--once", "--inputs-dir"])
+        .arg(inputs_dir);
+    if let Err(err) = cargo.status() {
+        eprintln!("error: cannot run cargo: {}", err);
+    }
+}
+
+/// Re-runs the day every time its source or input changes, until killed.
+fn watch(day: u8, inputs_dir: &Path) -> ! {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut watcher = Watcher::new(watch::watched_paths(root, day, inputs_dir));
+    println!("Watching day {}; press Ctrl-C to stop.", day);
+    rebuild_and_run(day, inputs_dir);
+    loop {
+        thread::sleep(watch::POLL_INTERVAL);
+        let changed = watcher.changed();
+        if changed.is_empty() {
+            continue;
+        }
+        let changed = changed
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>();
+        println!("\n--- changed: {} ---", changed.join(", "));
+        rebuild_and_run(day, inputs_dir);
+    }
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    match parse_args(&args) {
+        Ok(Command::Run {
+            days,
+            part,
+            input,
+            format,
+            threads,
+        }) => {
+            if run(days, part, &input, format, threads) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Ok(Command::Verify {
+            days,
+            input,
+            answers_dir,
+        }) => {
+            if verify(days, &input, &answers_dir) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Ok(Command::Bench {
+            days,
+            input,
+            iterations,
+        }) => match bench(days, &input, iterations) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(msg) => {
+                eprintln!("error: {}", msg);
+                ExitCode::FAILURE
+            }
+        },
+        Ok(Command::Fetch { days, inputs_dir }) => {
+            if fetch(&days, &inputs_dir) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Ok(Command::Submit {
+            day,
+            part,
+            input,
+            answers_dir,
+        }) => {
+            if submit(&day, part, &input, &answers_dir) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Ok(Command::New { day }) => {
+            if new_day(day) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Ok(Command::Watch {
+            day,
+            inputs_dir,
+            once,
+        }) => {
+            if !once {
+                watch(day, &inputs_dir);
+            }
+            if watch_once(day, &inputs_dir) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Ok(Command::Inventory {
+            input,
+            format,
+            bins,
+        }) => match inventory(&input, format, bins) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                ExitCode::FAILURE
+            }
+        },
+        Ok(Command::Score { input, rules }) => match score(&input, rules.as_deref()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                ExitCode::FAILURE
+            }
+        },
+        Err(msg) => {
+            eprintln!("error: {}", msg);
+            eprintln!("{}", USAGE);
+            ExitCode::from(2)
+        }
+    }
 }
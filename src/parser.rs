@@ -0,0 +1,265 @@
+//! Small building blocks for parsing puzzle input into typed values. Every
+//! failure is a [`ParseError`] pointing at the offending text.
+
+use crate::error::ParseError;
+use std::str::FromStr;
+
+/// One line of input together with its position, for locating errors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub day: u8,
+    /// 1-based.
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(day: u8, number: usize, text: &'a str) -> Self {
+        Line { day, number, text }
+    }
+
+    /// An error at `text`, which must be a slice of this line.
+    pub fn error(&self, text: &str, message: &str) -> ParseError {
+        ParseError::at(self.day, self.number, self.text, text, message)
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    pub fn int<T: FromStr>(&self, text: &'a str, message: &str) -> Result<T, ParseError> {
+        T::from_str(text).map_err(|_| self.error(text, message))
+    }
+
+    pub fn split_once(
+        &self,
+        text: &'a str,
+        separator: &str,
+        message: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        text.split_once(separator)
+            .ok_or_else(|| self.error(text, message))
+    }
+
+    /// Parses each `separator`-separated item of `text`.
+    pub fn list<T>(
+        &self,
+        text: &'a str,
+        separator: &str,
+        item: impl FnMut(&'a str) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        text.split(separator).map(item).collect()
+    }
+
+    /// Splits a `key <separator> value` line, trimming both sides.
+    pub fn key_value(
+        &self,
+        separator: &str,
+        message: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        let (key, value) = self.split_once(self.text, separator, message)?;
+        Ok((key.trim(), value.trim()))
+    }
+
+    /// Strips `literal` from the start of `text`, returning the rest.
+    pub fn literal(
+        &self,
+        text: &'a str,
+        literal: &str,
+        message: &str,
+    ) -> Result<&'a str, ParseError> {
+        text.strip_prefix(literal)
+            .ok_or_else(|| self.error(text, message))
+    }
+
+    /// Takes the first character of `text` if it is one of `allowed`.
+    pub fn one_of(
+        &self,
+        text: &'a str,
+        allowed: &str,
+        message: &str,
+    ) -> Result<(char, &'a str), ParseError> {
+        match text.chars().next() {
+            Some(c) if allowed.contains(c) => Ok((c, &text[c.len_utf8()..])),
+            _ => Err(self.error(text, message)),
+        }
+    }
+
+    /// Checks that nothing is left over after the last parsed value.
+    pub fn end(&self, rest: &'a str) -> Result<(), ParseError> {
+        if rest.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(rest, "unexpected trailing text"))
+        }
+    }
+
+    /// Checks that every character of `text` satisfies `predicate`,
+    /// reporting the first one that does not.
+    pub fn chars_where(
+        &self,
+        text: &'a str,
+        predicate: impl Fn(char) -> bool,
+        message: &str,
+    ) -> Result<&'a str, ParseError> {
+        match text.char_indices().find(|&(_, c)| !predicate(c)) {
+            Some((i, c)) => Err(self.error(&text[i..i + c.len_utf8()], message)),
+            None => Ok(text),
+        }
+    }
+
+    /// The value of each decimal digit in `text`.
+    pub fn digits(&self, text: &'a str, message: &str) -> Result<Vec<u8>, ParseError> {
+        self.chars_where(text, |c| c.is_ascii_digit(), message)?;
+        Ok(text.bytes().map(|b| b - b'0').collect())
+    }
+
+    /// Matches the whole line against `pattern`, where each `{}` stands for
+    /// a run of digits, and returns the runs. Any mismatch reports the line.
+    pub fn pattern<const N: usize>(
+        &self,
+        pattern: &str,
+        message: &str,
+    ) -> Result<[&'a str; N], ParseError> {
+        let mismatch = || self.error(self.text, message);
+        let mut literals = pattern.split("{}");
+        let mut rest = self.literal(self.text, literals.next().unwrap_or(""), message)?;
+        let mut numbers = [""; N];
+        for number in numbers.iter_mut() {
+            let len = rest.bytes().take_while(u8::is_ascii_digit).count();
+            if len == 0 {
+                return Err(mismatch());
+            }
+            *number = &rest[..len];
+            let literal = literals.next().ok_or_else(mismatch)?;
+            rest = rest[len..].strip_prefix(literal).ok_or_else(mismatch)?;
+        }
+        if literals.next().is_some() || !rest.is_empty() {
+            return Err(mismatch());
+        }
+        Ok(numbers)
+    }
+}
+
+pub fn lines(day: u8, input: &str) -> impl Iterator<Item = Line<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(move |(i, text)| Line::new(day, i + 1, text))
+}
+
+/// Parses every line of `input` with `parse`.
+pub fn each_line<'a, T>(
+    day: u8,
+    input: &'a str,
+    parse: impl FnMut(Line<'a>) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    lines(day, input).map(parse).collect()
+}
+
+/// Groups of consecutive non-blank lines; blank lines only separate groups.
+pub fn blocks(day: u8, input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut blocks = vec![Vec::new()];
+    for line in lines(day, input) {
+        if !line.is_blank() {
+            blocks.last_mut().unwrap().push(line);
+        } else if !blocks.last().unwrap().is_empty() {
+            blocks.push(Vec::new());
+        }
+    }
+    blocks.retain(|block| !block.is_empty());
+    blocks
+}
+
+/// Splits `text` into fixed-width columns of `width` characters; the last
+/// column may be shorter.
+pub fn columns(text: &str, width: usize) -> impl Iterator<Item = &str> {
+    text.char_indices()
+        .step_by(width.max(1))
+        .map(move |(start, _)| {
+            let end = text[start..]
+                .char_indices()
+                .nth(width)
+                .map_or(text.len(), |(i, _)| start + i);
+            &text[start..end]
+        })
+}
+
+#[test]
+fn test_line() {
+    let line = Line::new(1, 3, "2-4, 6-x");
+    let (first, second) = line.split_once(line.text, ",", "expected ','").unwrap();
+    assert_eq!(
+        vec![2, 4],
+        line.list(first, "-", |n| line.int::<u32>(n, "expected a number"))
+            .unwrap()
+    );
+    let err = line
+        .list(second.trim(), "-", |n| {
+            line.int::<u32>(n, "expected a number")
+        })
+        .unwrap_err();
+    assert_eq!((3, 8, "x"), (err.line, err.column, err.text.as_str()));
+
+    let line = Line::new(2, 1, "A Y!");
+    let (shape, rest) = line.one_of(line.text, "ABC", "expected A, B or C").unwrap();
+    let rest = line.literal(rest, " ", "expected a space").unwrap();
+    let (_, rest) = line.one_of(rest, "XYZ", "expected X, Y or Z").unwrap();
+    assert_eq!('A', shape);
+    assert_eq!(4, line.end(rest).unwrap_err().column);
+
+    let line = Line::new(8, 2, "30a7");
+    let err = line.digits(line.text, "expected a digit").unwrap_err();
+    assert_eq!((3, "a"), (err.column, err.text.as_str()));
+    assert_eq!(vec![3, 0], line.digits("30", "expected a digit").unwrap());
+}
+
+#[test]
+fn test_key_value() {
+    let line = Line::new(0, 1, " part1 = \"CMZ\" ");
+    assert_eq!(
+        ("part1", "\"CMZ\""),
+        line.key_value("=", "expected '='").unwrap()
+    );
+    assert!(Line::new(0, 1, "part1")
+        .key_value("=", "expected '='")
+        .is_err());
+}
+
+#[test]
+fn test_pattern() {
+    let line = Line::new(5, 1, "move 12 from 3 to 1");
+    let pattern = "move {} from {} to {}";
+    assert_eq!(
+        ["12", "3", "1"],
+        line.pattern(pattern, "expected a move").unwrap()
+    );
+    for text in [
+        "move x from 3 to 1",
+        "move 1 from 3 to 1 ",
+        "move 1 from 3",
+        "",
+    ] {
+        let err = Line::new(5, 1, text)
+            .pattern::<3>(pattern, "expected a move")
+            .unwrap_err();
+        assert_eq!(1, err.column, "{}", text);
+    }
+}
+
+#[test]
+fn test_blocks() {
+    let blocks = blocks(1, "\n1\n2\n\n\n3\n\n");
+    let numbers = blocks
+        .iter()
+        .map(|block| block.iter().map(|line| line.number).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(vec![vec![2, 3], vec![6]], numbers);
+}
+
+#[test]
+fn test_columns() {
+    let split = columns("    [D] [E]", 4).collect::<Vec<_>>();
+    assert_eq!(vec!["    ", "[D] ", "[E]"], split);
+    assert_eq!(0, columns("", 4).count());
+}
@@ -0,0 +1,142 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A 2D position; `x` grows to the right and `y` grows downwards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// The difference between two points, such as a single step.
+pub type Vec2 = Point;
+
+impl Point {
+    pub const ORIGIN: Point = Point::new(0, 0);
+
+    pub const fn new(x: i32, y: i32) -> Self {
+        Point { x, y }
+    }
+
+    /// Each coordinate reduced to -1, 0 or 1: the unit step towards `self`.
+    pub fn signum(self) -> Vec2 {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn manhattan(self, other: Point) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The number of king moves between the points.
+    pub fn chebyshev(self, other: Point) -> u32 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+}
+
+impl Add<Vec2> for Point {
+    type Output = Point;
+
+    fn add(self, other: Vec2) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign<Vec2> for Point {
+    fn add_assign(&mut self, other: Vec2) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vec2;
+
+    fn sub(self, other: Point) -> Vec2 {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign<Vec2> for Point {
+    fn sub_assign(&mut self, other: Vec2) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i32) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    pub const fn step(self) -> Vec2 {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+            Direction::Right => Point::new(1, 0),
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+#[test]
+fn test_arithmetic() {
+    let mut p = Point::new(3, -2);
+    assert_eq!(Point::new(4, 0), p + Point::new(1, 2));
+    assert_eq!(Point::new(2, -4), p - Point::new(1, 2));
+    assert_eq!(Point::new(-3, 2), -p);
+    assert_eq!(Point::new(9, -6), p * 3);
+    assert_eq!(Point::new(1, -1), p.signum());
+    assert_eq!(Point::ORIGIN, Point::ORIGIN.signum());
+    p += Direction::Up.step();
+    p -= Direction::Left.step();
+    assert_eq!(Point::new(4, -3), p);
+}
+
+#[test]
+fn test_distances() {
+    let (a, b) = (Point::new(1, 1), Point::new(-2, 3));
+    assert_eq!(5, a.manhattan(b));
+    assert_eq!(3, a.chebyshev(b));
+    assert_eq!(0, a.chebyshev(a));
+}
+
+#[test]
+fn test_directions() {
+    for direction in Direction::ALL {
+        assert_eq!(-direction.step(), direction.opposite().step());
+        assert_eq!(1, Point::ORIGIN.manhattan(Point::ORIGIN + direction.step()));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::solution::Day;
+
+/// Every implemented day, in order.
+pub fn days() -> Vec<Day> {
+    vec![
+        Day::of::<crate::day1::Day1>(),
+        Day::of::<crate::day2::Day2>(),
+        Day::of::<crate::day3::Day3>(),
+        Day::of::<crate::day4::Day4>(),
+        Day::of::<crate::day5::Day5>(),
+        Day::of::<crate::day6::Day6>(),
+        Day::of::<crate::day7::Day7>(),
+        Day::of::<crate::day8::Day8>(),
+        Day::of::<crate::day9::Day9>(),
+    ]
+}
+
+pub fn find(number: u8) -> Option<Day> {
+    days().into_iter().find(|day| day.number == number)
+}
+
+#[test]
+fn test_days_are_unique_and_ordered() {
+    let numbers = days().iter().map(|day| day.number).collect::<Vec<u8>>();
+    assert_eq!(Some(&1), numbers.first());
+    assert!(
+        numbers.windows(2).all(|pair| pair[0] < pair[1]),
+        "{:?}",
+        numbers
+    );
+}
+
+/// An example input with random edits: characters deleted, or replaced by
+/// ones that matter to some parser.
+#[cfg(test)]
+fn mangled_examples() -> impl proptest::strategy::Strategy<Value = (u8, String)> {
+    use proptest::prelude::*;
+
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(crate::examples::DEFAULT_DIR);
+    let examples = crate::examples::discover(&dir)
+        .unwrap()
+        .into_iter()
+        .map(|example| (example.day, std::fs::read_to_string(example.input).unwrap()))
+        .collect::<Vec<_>>();
+    let edit = (
+        any::<prop::sample::Index>(),
+        prop::option::of(prop::sample::select(
+            " \n0123456789-,[]$/.abcdzABCXYZUDLR"
+                .chars()
+                .collect::<Vec<_>>(),
+        )),
+    );
+    (
+        prop::sample::select(examples),
+        prop::collection::vec(edit, 1..8),
+    )
+        .prop_map(|((day, input), edits)| {
+            let mut chars = input.chars().collect::<Vec<_>>();
+            for (at, replacement) in edits {
+                if chars.is_empty() {
+                    break;
+                }
+                let at = at.index(chars.len());
+                match replacement {
+                    Some(ch) => chars[at] = ch,
+                    None => {
+                        chars.remove(at);
+                    }
+                }
+            }
+            (day, chars.into_iter().collect())
+        })
+}
+
+#[cfg(test)]
+fn parse_and_solve(day: &Day, input: &str) {
+    if let Ok(parsed) = day.parse(input) {
+        parsed.solve(crate::solution::Part::One);
+        parsed.solve(crate::solution::Part::Two);
+    }
+}
+
+/// Inputs that once parsed and then panicked while solving.
+#[test]
+fn test_known_inputs_do_not_panic() {
+    let cases = [
+        (1, "18446744073709551615\n\n18446744073709551615\n"),
+        (
+            7,
+            "$ cd /\n$ ls\n18446744073709551615 a\n18446744073709551615 b\n",
+        ),
+    ];
+    for (day, input) in cases {
+        parse_and_solve(&find(day).unwrap(), input);
+    }
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_no_input_panics(input in "\\PC*", bytes in proptest::prelude::any::<Vec<u8>>()) {
+        for day in days() {
+            parse_and_solve(&day, &input);
+            parse_and_solve(&day, &String::from_utf8_lossy(&bytes));
+        }
+    }
+
+    #[test]
+    fn test_no_mangled_example_panics((day, input) in mangled_examples()) {
+        parse_and_solve(&find(day).unwrap(), &input);
+    }
+}
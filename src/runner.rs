@@ -0,0 +1,397 @@
+use crate::bench::format_duration;
+use crate::error::Error;
+use crate::input::{self, InputSource};
+use crate::json::Object;
+use crate::solution::{Day, Part};
+use std::any::Any;
+use std::fmt::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartRun {
+    pub part: Part,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+/// The outcome of loading, parsing and solving the requested parts of a day.
+#[derive(Debug)]
+pub struct DayRun {
+    pub day: u8,
+    pub parts: Vec<Part>,
+    pub input_hash: Option<u64>,
+    pub parse_elapsed: Option<Duration>,
+    pub result: Result<Vec<PartRun>, Error>,
+}
+
+pub fn run_day(day: &Day, inputs: &InputSource, parts: &[Part]) -> DayRun {
+    let mut run = DayRun {
+        day: day.number,
+        parts: parts.to_vec(),
+        input_hash: None,
+        parse_elapsed: None,
+        result: Ok(Vec::new()),
+    };
+    let input = match inputs.load(day.number) {
+        Ok(input) => input,
+        Err(err) => {
+            run.result = Err(err.into());
+            return run;
+        }
+    };
+    run.input_hash = Some(input::hash(&input));
+
+    let start = Instant::now();
+    let parsed = day.parse(&input);
+    run.parse_elapsed = Some(start.elapsed());
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            run.result = Err(err.into());
+            return run;
+        }
+    };
+
+    let part_runs = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = parsed.solve(part);
+            PartRun {
+                part,
+                answer,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect();
+    run.result = Ok(part_runs);
+    run
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_owned()
+    }
+}
+
+/// Like [`run_day`], but a panic while parsing or solving is reported as
+/// [`Error::Panic`] instead of unwinding further.
+pub fn run_day_catching(day: &Day, inputs: &InputSource, parts: &[Part]) -> DayRun {
+    panic::catch_unwind(AssertUnwindSafe(|| run_day(day, inputs, parts))).unwrap_or_else(
+        |payload| DayRun {
+            day: day.number,
+            parts: parts.to_vec(),
+            input_hash: None,
+            parse_elapsed: None,
+            result: Err(Error::Panic {
+                day: day.number,
+                message: panic_message(payload.as_ref()),
+            }),
+        },
+    )
+}
+
+/// The number of worker threads to use when nothing else is asked for.
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+/// Every day run by [`run_all`], in the order they were given.
+#[derive(Debug)]
+pub struct Summary {
+    pub runs: Vec<DayRun>,
+    pub threads: usize,
+    pub wall: Duration,
+}
+
+/// Runs `days` on a pool of `threads` workers, each taking the next day not
+/// yet started. A day that panics is reported without stopping the others.
+pub fn run_all(days: &[Day], inputs: &InputSource, parts: &[Part], threads: usize) -> Summary {
+    let threads = threads.clamp(1, days.len().max(1));
+    let next = AtomicUsize::new(0);
+    let start = Instant::now();
+    let mut runs = thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut runs = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(day) = days.get(i) else {
+                            return runs;
+                        };
+                        runs.push((i, run_day_catching(day, inputs, parts)));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    let wall = start.elapsed();
+    runs.sort_by_key(|&(i, _)| i);
+    Summary {
+        runs: runs.into_iter().map(|(_, run)| run).collect(),
+        threads,
+        wall,
+    }
+}
+
+impl Summary {
+    /// Time spent parsing and solving, summed over every day.
+    pub fn cpu(&self) -> Duration {
+        self.runs.iter().map(DayRun::elapsed).sum()
+    }
+
+    /// One aligned row per day and part, then the wall and CPU times.
+    pub fn table(&self) -> String {
+        let mut rows = vec![[
+            "day".to_owned(),
+            "part".to_owned(),
+            "answer".to_owned(),
+            "time".to_owned(),
+            "status".to_owned(),
+        ]];
+        for run in &self.runs {
+            match &run.result {
+                Ok(part_runs) => rows.extend(part_runs.iter().map(|part_run| {
+                    [
+                        run.day.to_string(),
+                        part_run.part.number().to_string(),
+                        part_run.answer.clone(),
+                        format_duration(part_run.elapsed),
+                        "ok".to_owned(),
+                    ]
+                })),
+                Err(err) => rows.extend(run.parts.iter().map(|part| {
+                    [
+                        run.day.to_string(),
+                        part.number().to_string(),
+                        "-".to_owned(),
+                        "-".to_owned(),
+                        format!("ERROR ({})", err),
+                    ]
+                })),
+            }
+        }
+        let mut widths = [0; 4];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let mut out = String::new();
+        for [day, part, answer, time, status] in &rows {
+            let _ = writeln!(
+                out,
+                "{:<w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {}",
+                day,
+                part,
+                answer,
+                time,
+                status,
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+                w3 = widths[3]
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{} days, {} threads: wall time {}, CPU time {}",
+            self.runs.len(),
+            self.threads,
+            format_duration(self.wall),
+            format_duration(self.cpu())
+        );
+        out
+    }
+}
+
+fn error_json(err: &Error) -> Object {
+    let object = Object::new();
+    match err {
+        Error::Input(err) => object
+            .str("kind", "input")
+            .str("message", &err.to_string())
+            .str("source", &err.source_name),
+        Error::Parse(err) => object
+            .str("kind", "parse")
+            .str("message", &err.message)
+            .num("line", err.line as u64)
+            .num("column", err.column as u64)
+            .str("text", &err.text),
+        Error::Answers(err) => object
+            .str("kind", "answers")
+            .str("message", &err.to_string()),
+        Error::Panic { message, .. } => object.str("kind", "panic").str("message", message),
+    }
+}
+
+impl DayRun {
+    /// Time spent parsing and solving the parts that finished.
+    pub fn elapsed(&self) -> Duration {
+        let parts = match &self.result {
+            Ok(part_runs) => part_runs.iter().map(|run| run.elapsed).sum(),
+            Err(_) => Duration::ZERO,
+        };
+        self.parse_elapsed.unwrap_or_default() + parts
+    }
+
+    /// One JSON object per requested part.
+    pub fn to_json(&self) -> Vec<Object> {
+        let base = |part: Part| {
+            let object = Object::new()
+                .num("day", self.day)
+                .num("part", part.number());
+            let object = match self.input_hash {
+                Some(hash) => object.str("input_hash", &format!("{:016x}", hash)),
+                None => object.null("input_hash"),
+            };
+            match self.parse_elapsed {
+                Some(elapsed) => object.num("parse_ns", elapsed.as_nanos()),
+                None => object.null("parse_ns"),
+            }
+        };
+        match &self.result {
+            Ok(part_runs) => part_runs
+                .iter()
+                .map(|run| {
+                    base(run.part)
+                        .str("status", "ok")
+                        .str("answer", &run.answer)
+                        .num("elapsed_ns", run.elapsed.as_nanos())
+                })
+                .collect(),
+            Err(err) => self
+                .parts
+                .iter()
+                .map(|&part| {
+                    base(part)
+                        .str("status", "error")
+                        .null("answer")
+                        .null("elapsed_ns")
+                        .object("error", error_json(err))
+                })
+                .collect(),
+        }
+    }
+}
+
+#[test]
+fn test_run_day_json() {
+    let dir = std::env::temp_dir().join(format!("aoc22-runner-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("day9.txt"), "R 4\nU 4\n").unwrap();
+    std::fs::write(dir.join("day4.txt"), "2-4,6-8\n2-x,4-5\n").unwrap();
+    let inputs = InputSource::Dir(dir.clone());
+
+    let day = crate::registry::find(9).unwrap();
+    let run = run_day(&day, &inputs, &[Part::One]);
+    let json = run.to_json();
+    assert_eq!(1, json.len());
+    let line = json[0].to_string();
+    assert!(
+        line.starts_with(r#"{"day":9,"part":1,"input_hash":""#),
+        "{}",
+        line
+    );
+    assert!(
+        line.contains(r#""status":"ok","answer":"7","elapsed_ns":"#),
+        "{}",
+        line
+    );
+
+    let day = crate::registry::find(4).unwrap();
+    let json = run_day(&day, &inputs, &Part::BOTH).to_json();
+    assert_eq!(2, json.len());
+    assert!(json[1].to_string().ends_with(
+        r#""status":"error","answer":null,"elapsed_ns":null,"error":{"kind":"parse","message":"expected a section number","line":2,"column":3,"text":"x"}}"#
+    ));
+
+    let day = crate::registry::find(1).unwrap();
+    let json = run_day(&day, &inputs, &[Part::Two]).to_json();
+    let line = json[0].to_string();
+    assert!(
+        line.contains(r#""input_hash":null,"parse_ns":null,"status":"error""#),
+        "{}",
+        line
+    );
+    assert!(line.contains(r#""kind":"input""#), "{}", line);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_run_all() {
+    use crate::error::ParseError;
+    use crate::solution::Solution;
+
+    struct Panics;
+
+    impl Solution for Panics {
+        const DAY: u8 = 99;
+
+        type Input = ();
+        type Answer = u8;
+
+        fn parse(_input: &str) -> Result<Self::Input, ParseError> {
+            Ok(())
+        }
+
+        fn part1(_input: &Self::Input) -> Self::Answer {
+            1
+        }
+
+        fn part2(_input: &Self::Input) -> Self::Answer {
+            panic!("no answer yet")
+        }
+    }
+
+    let dir = std::env::temp_dir().join(format!("aoc22-run-all-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("day9.txt"), "R 4\nU 4\n").unwrap();
+    std::fs::write(dir.join("day99.txt"), "").unwrap();
+    std::fs::write(dir.join("day6.txt"), "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n").unwrap();
+    let inputs = InputSource::Dir(dir.clone());
+    let days = [
+        crate::registry::find(9).unwrap(),
+        Day::of::<Panics>(),
+        crate::registry::find(6).unwrap(),
+    ];
+
+    let summary = run_all(&days, &inputs, &Part::BOTH, 2);
+    assert_eq!(
+        vec![9, 99, 6],
+        summary.runs.iter().map(|run| run.day).collect::<Vec<_>>()
+    );
+    assert!(summary.runs[0].result.is_ok() && summary.runs[2].result.is_ok());
+    assert!(matches!(
+        &summary.runs[1].result,
+        Err(Error::Panic { day: 99, message }) if message == "no answer yet"
+    ));
+    assert!(summary.cpu() > Duration::ZERO);
+
+    let table = summary.table();
+    let lines = table.lines().collect::<Vec<_>>();
+    assert!(lines[0].starts_with("day  part  answer  "), "{}", table);
+    assert!(lines[1].starts_with("9    1     7       "), "{}", table);
+    assert!(
+        lines[4].ends_with("ERROR (day 99 panicked: no answer yet)"),
+        "{}",
+        table
+    );
+    assert_eq!(8, lines.len());
+    assert!(lines[7].starts_with("3 days, 2 threads: wall time "));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
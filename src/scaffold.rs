@@ -0,0 +1,260 @@
+use crate::{examples, input};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const TEMPLATE: &str = include_str!("../templates/day.rs.in");
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    /// The day already has a module or is already registered.
+    Exists(PathBuf),
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// A file that must be edited does not look as expected.
+    Layout {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::Exists(path) => write!(f, "{} already exists", path.display()),
+            ScaffoldError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            ScaffoldError::Layout { path, message } => {
+                write!(f, "{}: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+/// What [`new_day`] wrote, relative to the project root.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Scaffold {
+    pub created: Vec<PathBuf>,
+    pub updated: Vec<PathBuf>,
+}
+
+pub fn render(day: u8) -> String {
+    TEMPLATE.replace("{{DAY}}", &day.to_string())
+}
+
+/// Inserts `pub mod dayN;` among the other `pub mod` lines, in the order
+/// rustfmt keeps them.
+fn register_module(lib: &str, day: u8) -> Option<String> {
+    let module = format!("day{}", day);
+    let line = format!("pub mod {};", module);
+    let mut lines = lib.lines().collect::<Vec<_>>();
+    let modules = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| Some((i, line.strip_prefix("pub mod ")?.strip_suffix(';')?)))
+        .collect::<Vec<_>>();
+    let (last, _) = *modules.last()?;
+    let at = modules
+        .iter()
+        .find(|(_, name)| *name > module.as_str())
+        .map_or(last + 1, |&(i, _)| i);
+    lines.insert(at, &line);
+    Some(lines.join("\n") + "\n")
+}
+
+/// The day registered on a line such as `Day::of::<crate::day9::Day9>(),`.
+fn registered_day(line: &str) -> Option<u8> {
+    let rest = line.trim().strip_prefix("Day::of::<crate::day")?;
+    u8::from_str(rest.split_once("::")?.0).ok()
+}
+
+/// Inserts the new day into the list in `registry::days`, keeping it ordered.
+fn register_day(registry: &str, day: u8) -> Option<String> {
+    let mut lines = registry.lines().collect::<Vec<_>>();
+    let registered = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| Some((i, registered_day(line)?)))
+        .collect::<Vec<_>>();
+    let &(first, _) = registered.first()?;
+    let indent_len = lines[first].len() - lines[first].trim_start().len();
+    let line = format!(
+        "{}Day::of::<crate::day{}::Day{}>(),",
+        &lines[first][..indent_len],
+        day,
+        day
+    );
+    let at = registered
+        .iter()
+        .find(|&&(_, number)| number > day)
+        .map_or(registered.last()?.0 + 1, |&(i, _)| i);
+    lines.insert(at, &line);
+    Some(lines.join("\n") + "\n")
+}
+
+fn read(path: &Path) -> Result<String, ScaffoldError> {
+    fs::read_to_string(path).map_err(|error| ScaffoldError::Io {
+        path: path.to_owned(),
+        error,
+    })
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), ScaffoldError> {
+    let io_error = |error| ScaffoldError::Io {
+        path: path.to_owned(),
+        error,
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    fs::write(path, contents).map_err(io_error)
+}
+
+/// Empty answers, which no solver produces, so the example fails until
+/// they are filled in.
+fn example_answers(day: u8) -> String {
+    format!(
+        "# Answers for examples/day{}.txt, from the puzzle text.\n\
+         part1 = \"\"\n\
+         part2 = \"\"\n",
+        day
+    )
+}
+
+/// Adds day `day` to the project at `root`: a module from the template
+/// registered in `src/lib.rs` and `src/registry.rs`, an empty input and an
+/// example fixture whose test fails until its answers are filled in.
+/// Existing inputs and examples are kept.
+pub fn new_day(root: &Path, day: u8) -> Result<Scaffold, ScaffoldError> {
+    let module = Path::new("src").join(format!("day{}.rs", day));
+    let lib = Path::new("src/lib.rs");
+    let registry = Path::new("src/registry.rs");
+    if root.join(&module).exists() {
+        return Err(ScaffoldError::Exists(module));
+    }
+    let layout_error = |path: &Path, message: &str| ScaffoldError::Layout {
+        path: path.to_owned(),
+        message: message.to_owned(),
+    };
+
+    let lib_text = read(&root.join(lib))?;
+    if lib_text
+        .lines()
+        .any(|line| line == format!("pub mod day{};", day))
+    {
+        return Err(ScaffoldError::Exists(lib.to_owned()));
+    }
+    let lib_text =
+        register_module(&lib_text, day).ok_or_else(|| layout_error(lib, "no 'pub mod' lines"))?;
+    let registry_text = read(&root.join(registry))?;
+    if registry_text
+        .lines()
+        .any(|line| registered_day(line) == Some(day))
+    {
+        return Err(ScaffoldError::Exists(registry.to_owned()));
+    }
+    let registry_text = register_day(&registry_text, day)
+        .ok_or_else(|| layout_error(registry, "no registered days"))?;
+
+    let mut scaffold = Scaffold::default();
+    write(&root.join(&module), &render(day))?;
+    scaffold.created.push(module);
+    write(&root.join(lib), &lib_text)?;
+    write(&root.join(registry), &registry_text)?;
+    scaffold
+        .updated
+        .extend([lib.to_owned(), registry.to_owned()]);
+
+    let examples_dir = Path::new(examples::DEFAULT_DIR);
+    let new_files = [
+        (
+            input::day_file(Path::new(input::DEFAULT_DIR), day),
+            String::new(),
+        ),
+        (input::day_file(examples_dir, day), String::new()),
+        (
+            examples_dir.join(format!("day{}.toml", day)),
+            example_answers(day),
+        ),
+    ];
+    for (path, contents) in new_files {
+        if !root.join(&path).exists() {
+            write(&root.join(&path), &contents)?;
+            scaffold.created.push(path);
+        }
+    }
+    Ok(scaffold)
+}
+
+#[test]
+fn test_register_module() {
+    let lib = "//! Docs.\n\npub mod answers;\npub mod day1;\npub mod day2;\npub mod error;\n";
+    let lib = register_module(lib, 10).unwrap();
+    assert!(lib.contains("pub mod day1;\npub mod day10;\npub mod day2;\n"));
+    let lib = register_module(&lib, 25).unwrap();
+    assert!(lib.contains("pub mod day2;\npub mod day25;\npub mod error;\n"));
+    assert_eq!(None, register_module("fn main() {}\n", 3));
+}
+
+#[test]
+fn test_register_day() {
+    let registry = "    vec![\n        Day::of::<crate::day1::Day1>(),\n        \
+                    Day::of::<crate::day3::Day3>(),\n    ]\n";
+    let registry = register_day(registry, 2).unwrap();
+    let registry = register_day(&registry, 12).unwrap();
+    let days = registry
+        .lines()
+        .filter_map(registered_day)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![1, 2, 3, 12], days);
+    assert!(registry.contains("\n        Day::of::<crate::day12::Day12>(),\n    ]"));
+}
+
+#[test]
+fn test_new_day() {
+    let root = std::env::temp_dir().join(format!("aoc22-scaffold-{}", std::process::id()));
+    let lib = "pub mod day1;\npub mod day9;\npub mod registry;\n";
+    let registry = "pub fn days() -> Vec<Day> {\n    vec![\n        \
+                    Day::of::<crate::day1::Day1>(),\n        \
+                    Day::of::<crate::day9::Day9>(),\n    ]\n}\n";
+    write(&root.join("src/lib.rs"), lib).unwrap();
+    write(&root.join("src/registry.rs"), registry).unwrap();
+    write(&root.join("inputs/day10.txt"), "kept\n").unwrap();
+
+    let scaffold = new_day(&root, 10).unwrap();
+    assert_eq!(
+        vec![
+            PathBuf::from("src/day10.rs"),
+            PathBuf::from("examples/day10.txt"),
+            PathBuf::from("examples/day10.toml")
+        ],
+        scaffold.created
+    );
+    assert_eq!("kept\n", read(&root.join("inputs/day10.txt")).unwrap());
+    let module = read(&root.join("src/day10.rs")).unwrap();
+    assert!(module.contains("pub struct Day10;") && module.contains("const DAY: u8 = 10;"));
+    assert!(module.contains("fn test_example()") && module.contains("examples/day10.toml"));
+    let answers = read(&root.join("examples/day10.toml")).unwrap();
+    assert_eq!(
+        Some(""),
+        crate::answers::parse(10, &answers)
+            .unwrap()
+            .get(crate::solution::Part::One)
+    );
+    assert_eq!(
+        "pub mod day1;\npub mod day10;\npub mod day9;\npub mod registry;\n",
+        read(&root.join("src/lib.rs")).unwrap()
+    );
+    assert!(read(&root.join("src/registry.rs"))
+        .unwrap()
+        .contains("Day9>(),\n        Day::of::<crate::day10::Day10>(),\n    ]"));
+
+    assert!(matches!(new_day(&root, 10), Err(ScaffoldError::Exists(_))));
+    fs::remove_file(root.join("src/day10.rs")).unwrap();
+    assert!(matches!(new_day(&root, 10), Err(ScaffoldError::Exists(_))));
+
+    fs::remove_dir_all(&root).unwrap();
+}
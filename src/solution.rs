@@ -0,0 +1,73 @@
+use crate::error::ParseError;
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    /// The puzzle's `level`: 1 or 2.
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+/// A day's puzzle: the input is parsed once and shared by both parts.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+    type Answer: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Answer;
+    fn part2(input: &Self::Input) -> Self::Answer;
+}
+
+/// A parsed input with the day's concrete types erased, so that days can be
+/// driven without knowing their `Input` or `Answer` types.
+pub trait Parsed {
+    fn solve(&self, part: Part) -> String;
+}
+
+struct ParsedInput<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for ParsedInput<S> {
+    fn solve(&self, part: Part) -> String {
+        match part {
+            Part::One => S::part1(&self.0).to_string(),
+            Part::Two => S::part2(&self.0).to_string(),
+        }
+    }
+}
+
+fn parse_boxed<S: Solution + 'static>(input: &str) -> Result<Box<dyn Parsed>, ParseError> {
+    Ok(Box::new(ParsedInput::<S>(S::parse(input)?)))
+}
+
+/// A registry entry for one implemented day.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: u8,
+    parse: fn(&str) -> Result<Box<dyn Parsed>, ParseError>,
+}
+
+impl Day {
+    pub fn of<S: Solution + 'static>() -> Self {
+        Day {
+            number: S::DAY,
+            parse: parse_boxed::<S>,
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Parsed>, ParseError> {
+        (self.parse)(input)
+    }
+}
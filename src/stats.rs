@@ -0,0 +1,294 @@
+//! Summary statistics for groups of numbers, such as the calories each elf
+//! carries in day 1. Totals are `u128`, so no group of `u64` values can
+//! overflow them.
+
+use crate::error::ParseError;
+use crate::json::{self, Object};
+use crate::parser;
+use std::fmt::Write;
+
+/// The percentiles reported for every group.
+pub const PERCENTILES: [u8; 5] = [10, 25, 50, 75, 90];
+pub const DEFAULT_BINS: usize = 5;
+/// The most bins a histogram may be asked for; each one is printed.
+pub const MAX_BINS: usize = 1000;
+
+/// Counts of values in equal-width bins, the first starting at `start`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Histogram {
+    pub start: u64,
+    pub width: u128,
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Splits the range of `sorted` into at most `bins` bins.
+    fn of(sorted: &[u64], bins: usize) -> Self {
+        let (start, end) = (sorted[0], sorted[sorted.len() - 1]);
+        let span = (end - start) as u128 + 1;
+        let width = span.div_ceil(bins.max(1) as u128);
+        let mut counts = vec![0; span.div_ceil(width) as usize];
+        for &value in sorted {
+            counts[((value - start) as u128 / width) as usize] += 1;
+        }
+        Histogram {
+            start,
+            width,
+            counts,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub total: u128,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub median: f64,
+    /// Nearest-rank percentiles, one for each of [`PERCENTILES`].
+    pub percentiles: Vec<(u8, u64)>,
+    pub histogram: Histogram,
+}
+
+impl Summary {
+    /// Summarizes a group of values; `None` if it is empty.
+    pub fn of(values: &[u64], bins: usize) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_unstable();
+        let count = sorted.len();
+        let total = sorted.iter().map(|&value| value as u128).sum::<u128>();
+        let middle = count / 2;
+        let median = if count.is_multiple_of(2) {
+            (sorted[middle - 1] as f64 + sorted[middle] as f64) / 2.0
+        } else {
+            sorted[middle] as f64
+        };
+        let percentiles = PERCENTILES
+            .iter()
+            .map(|&p| {
+                let rank = (p as usize * count).div_ceil(100).max(1);
+                (p, sorted[rank - 1])
+            })
+            .collect();
+        Some(Summary {
+            count,
+            total,
+            min: sorted[0],
+            max: sorted[count - 1],
+            mean: total as f64 / count as f64,
+            median,
+            percentiles,
+            histogram: Histogram::of(&sorted, bins),
+        })
+    }
+}
+
+/// Groups of numbers, one per line, separated by blank lines.
+pub fn parse_groups(day: u8, input: &str) -> Result<Vec<Vec<u64>>, ParseError> {
+    parser::blocks(day, input)
+        .iter()
+        .map(|block| {
+            block
+                .iter()
+                .map(|line| line.int(line.text.trim(), "expected a number"))
+                .collect()
+        })
+        .collect()
+}
+
+/// Summarizes every non-empty group, keeping its index.
+pub fn summarize(groups: &[Vec<u64>], bins: usize) -> Vec<(usize, Summary)> {
+    groups
+        .iter()
+        .enumerate()
+        .filter_map(|(i, group)| Some((i, Summary::of(group, bins)?)))
+        .collect()
+}
+
+fn counts(histogram: &Histogram, separator: &str) -> String {
+    let counts = histogram
+        .counts
+        .iter()
+        .map(usize::to_string)
+        .collect::<Vec<_>>();
+    counts.join(separator)
+}
+
+fn header(first: &str) -> Vec<String> {
+    let mut header = ["count", "total", "min", "max", "mean", "median"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>();
+    header.insert(0, first.to_owned());
+    header.extend(PERCENTILES.iter().map(|p| format!("p{}", p)));
+    header
+}
+
+fn row(index: usize, summary: &Summary) -> Vec<String> {
+    let mut row = vec![
+        index.to_string(),
+        summary.count.to_string(),
+        summary.total.to_string(),
+        summary.min.to_string(),
+        summary.max.to_string(),
+        format!("{:.1}", summary.mean),
+        format!("{:.1}", summary.median),
+    ];
+    row.extend(
+        summary
+            .percentiles
+            .iter()
+            .map(|(_, value)| value.to_string()),
+    );
+    row
+}
+
+/// An aligned table with one row per group, its histogram last.
+pub fn report(summaries: &[(usize, Summary)], label: &str) -> String {
+    let mut rows = vec![header(label)];
+    rows[0].push("histogram".to_owned());
+    for (index, summary) in summaries {
+        let mut row = row(*index, summary);
+        row.push(format!(
+            "{} (from {}, width {})",
+            counts(&summary.histogram, " "),
+            summary.histogram.start,
+            summary.histogram.width
+        ));
+        rows.push(row);
+    }
+    let columns = rows[0].len();
+    let widths = (0..columns)
+        .map(|i| rows.iter().map(|row| row[i].len()).max().unwrap_or(0))
+        .collect::<Vec<_>>();
+    let mut out = String::new();
+    for row in &rows {
+        let cells = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| match i {
+                0 => format!("{:<width$}", cell, width = width),
+                _ if i == columns - 1 => cell.clone(),
+                _ => format!("{:>width$}", cell, width = width),
+            })
+            .collect::<Vec<_>>();
+        let _ = writeln!(out, "{}", cells.join("  "));
+    }
+    out
+}
+
+/// CSV with a header row; histogram counts are separated by `;`.
+pub fn csv(summaries: &[(usize, Summary)], label: &str) -> String {
+    let mut header = header(label);
+    header.extend(["histogram_start", "histogram_width", "histogram_counts"].map(String::from));
+    let mut out = header.join(",") + "\n";
+    for (index, summary) in summaries {
+        let mut row = row(*index, summary);
+        row.push(summary.histogram.start.to_string());
+        row.push(summary.histogram.width.to_string());
+        row.push(counts(&summary.histogram, ";"));
+        out += &(row.join(",") + "\n");
+    }
+    out
+}
+
+/// One JSON object per group.
+pub fn to_json(summaries: &[(usize, Summary)], label: &str) -> Vec<Object> {
+    summaries
+        .iter()
+        .map(|(index, summary)| {
+            let percentiles = summary
+                .percentiles
+                .iter()
+                .fold(Object::new(), |object, &(p, value)| {
+                    object.num(&format!("p{}", p), value)
+                });
+            let histogram = Object::new()
+                .num("start", summary.histogram.start)
+                .num("width", summary.histogram.width)
+                .raw("counts", json::array(&summary.histogram.counts));
+            Object::new()
+                .num(label, *index as u64)
+                .num("count", summary.count as u64)
+                .num("total", summary.total)
+                .num("min", summary.min)
+                .num("max", summary.max)
+                .float("mean", summary.mean)
+                .float("median", summary.median)
+                .object("percentiles", percentiles)
+                .object("histogram", histogram)
+        })
+        .collect()
+}
+
+#[test]
+fn test_summary() {
+    let summary = Summary::of(&[7000, 8000, 9000, 1000, 10000, 2000], 3).unwrap();
+    assert_eq!(
+        (6, 37000, 1000, 10000),
+        (summary.count, summary.total, summary.min, summary.max)
+    );
+    assert_eq!((6166.666666666667, 7500.0), (summary.mean, summary.median));
+    assert_eq!(
+        vec![(10, 1000), (25, 2000), (50, 7000), (75, 9000), (90, 10000)],
+        summary.percentiles
+    );
+    assert_eq!(
+        Histogram {
+            start: 1000,
+            width: 3001,
+            counts: vec![2, 1, 3]
+        },
+        summary.histogram
+    );
+    assert_eq!(None, Summary::of(&[], 3));
+
+    // Totals and spans past u64::MAX.
+    let summary = Summary::of(&[u64::MAX, u64::MAX, 0], 1).unwrap();
+    assert_eq!(2 * u64::MAX as u128, summary.total);
+    assert_eq!(vec![3], summary.histogram.counts);
+    let summary = Summary::of(&[4, 4], 5).unwrap();
+    assert_eq!(
+        (1, vec![2]),
+        (summary.histogram.width, summary.histogram.counts)
+    );
+}
+
+#[test]
+fn test_exports() {
+    let groups = parse_groups(1, "1000\n2000\n3000\n\n\n4000\n").unwrap();
+    assert_eq!(vec![vec![1000, 2000, 3000], vec![4000]], groups);
+    let summaries = summarize(&groups, 2);
+
+    let csv = csv(&summaries, "elf");
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(
+        "elf,count,total,min,max,mean,median,p10,p25,p50,p75,p90,\
+         histogram_start,histogram_width,histogram_counts",
+        lines[0]
+    );
+    assert_eq!(
+        "0,3,6000,1000,3000,2000.0,2000.0,1000,1000,2000,3000,3000,1000,1001,2;1",
+        lines[1]
+    );
+    assert_eq!(
+        r#"{"elf":1,"count":1,"total":4000,"min":4000,"max":4000,"mean":4000,"median":4000,"percentiles":{"p10":4000,"p25":4000,"p50":4000,"p75":4000,"p90":4000},"histogram":{"start":4000,"width":1,"counts":[1]}}"#,
+        to_json(&summaries, "elf")[1].to_string()
+    );
+    let report = report(&summaries, "elf");
+    assert!(
+        report.starts_with("elf  count  total   min   max"),
+        "{}",
+        report
+    );
+    assert!(report.contains("2 1 (from 1000, width 1001)"), "{}", report);
+
+    let err = parse_groups(1, "1000\n-5\n").err().unwrap();
+    assert_eq!((2, "expected a number"), (err.line, err.message.as_str()));
+}
@@ -0,0 +1,480 @@
+use crate::http::{self, Http, HttpError};
+use crate::solution::Part;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The submission history, kept next to the recorded answers.
+pub const HISTORY_FILE: &str = "submissions.tsv";
+/// Seconds to wait between any two submissions.
+pub const MIN_INTERVAL: u64 = 60;
+
+/// What adventofcode.com said about a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after the previous answer.
+    RateLimited {
+        wait: u64,
+    },
+    /// The part is already solved or not unlocked yet.
+    WrongLevel,
+    Unknown,
+}
+
+impl Verdict {
+    pub fn is_wrong(self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::TooHigh => write!(f, "too-high"),
+            Verdict::TooLow => write!(f, "too-low"),
+            Verdict::RateLimited { wait } => write!(f, "rate-limited:{}", wait),
+            Verdict::WrongLevel => write!(f, "wrong-level"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "correct" => Ok(Verdict::Correct),
+            "wrong" => Ok(Verdict::Wrong),
+            "too-high" => Ok(Verdict::TooHigh),
+            "too-low" => Ok(Verdict::TooLow),
+            "wrong-level" => Ok(Verdict::WrongLevel),
+            "unknown" => Ok(Verdict::Unknown),
+            _ => {
+                let wait = s.strip_prefix("rate-limited:").ok_or(())?;
+                let wait = u64::from_str(wait).map_err(|_| ())?;
+                Ok(Verdict::RateLimited { wait })
+            }
+        }
+    }
+}
+
+/// Reads the verdict out of the page returned for a submission.
+pub fn parse_verdict(page: &str) -> Verdict {
+    if page.contains("That's the right answer") {
+        Verdict::Correct
+    } else if page.contains("That's not the right answer") {
+        if page.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if page.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if page.contains("You gave an answer too recently") {
+        Verdict::RateLimited {
+            wait: parse_wait(page).unwrap_or(MIN_INTERVAL),
+        }
+    } else if page.contains("You don't seem to be solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        Verdict::Unknown
+    }
+}
+
+/// Parses "You have 4m 12s left to wait" into seconds.
+fn parse_wait(page: &str) -> Option<u64> {
+    let start = page.find("You have ")? + "You have ".len();
+    let end = start + page[start..].find(" left to wait")?;
+    page[start..end]
+        .split_whitespace()
+        .try_fold(0u64, |total, part| {
+            let (number, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+            let number = u64::from_str(number).ok()?;
+            let seconds = match unit {
+                "h" => number.checked_mul(3600)?,
+                "m" => number.checked_mul(60)?,
+                "s" => number,
+                _ => return None,
+            };
+            total.checked_add(seconds)
+        })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attempt {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub day: u8,
+    pub part: Part,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}",
+            self.timestamp,
+            self.day,
+            self.part.number(),
+            self.answer,
+            self.verdict
+        )
+    }
+}
+
+fn parse_attempt(line: &str) -> Option<Attempt> {
+    let [timestamp, day, part, answer, verdict] =
+        line.split('\t').collect::<Vec<_>>().try_into().ok()?;
+    Some(Attempt {
+        timestamp: u64::from_str(timestamp).ok()?,
+        day: u8::from_str(day).ok()?,
+        part: match part {
+            "1" => Part::One,
+            "2" => Part::Two,
+            _ => return None,
+        },
+        answer: answer.to_owned(),
+        verdict: Verdict::from_str(verdict).ok()?,
+    })
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    Refused(Refusal),
+    /// No session cookie is configured.
+    NoSession,
+    Http(HttpError),
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    History {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Refused(refusal) => write!(f, "not submitted: {}", refusal),
+            SubmitError::NoSession => write!(
+                f,
+                "no session cookie: set {} or write it to {}",
+                http::SESSION_VAR,
+                http::SESSION_FILE
+            ),
+            SubmitError::Http(err) => err.fmt(f),
+            SubmitError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            SubmitError::History {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+        }
+    }
+}
+
+impl From<HttpError> for SubmitError {
+    fn from(err: HttpError) -> Self {
+        SubmitError::Http(err)
+    }
+}
+
+/// Every recorded attempt, oldest first. A missing file is an empty history.
+pub fn load_history(path: &Path) -> Result<Vec<Attempt>, SubmitError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(SubmitError::Io {
+                path: path.to_owned(),
+                error,
+            })
+        }
+    };
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            parse_attempt(line).ok_or_else(|| SubmitError::History {
+                path: path.to_owned(),
+                line: i + 1,
+                message: "expected tab-separated timestamp, day, part, answer and verdict"
+                    .to_owned(),
+            })
+        })
+        .collect()
+}
+
+fn append_history(path: &Path, attempt: &Attempt) -> Result<(), SubmitError> {
+    let io_error = |error| SubmitError::Io {
+        path: path.to_owned(),
+        error,
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(io_error)?;
+    writeln!(file, "{}", attempt).map_err(io_error)
+}
+
+/// Why an answer was not sent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Refusal {
+    AlreadySolved {
+        answer: String,
+    },
+    KnownWrong {
+        verdict: Verdict,
+    },
+    /// The answer is empty or contains a tab or newline.
+    Invalid,
+    TooSoon {
+        wait: u64,
+    },
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::AlreadySolved { answer } => write!(f, "already solved with {}", answer),
+            Refusal::KnownWrong { verdict } => {
+                write!(f, "this answer is known to be wrong ({})", verdict)
+            }
+            Refusal::Invalid => write!(f, "the answer is empty or malformed"),
+            Refusal::TooSoon { wait } => write!(f, "wait {}s before submitting again", wait),
+        }
+    }
+}
+
+/// Decides from the history whether `answer` may be submitted at `now`.
+pub fn check(
+    history: &[Attempt],
+    day: u8,
+    part: Part,
+    answer: &str,
+    now: u64,
+) -> Result<(), Refusal> {
+    if answer.is_empty() || answer.contains(['\t', '\n']) {
+        return Err(Refusal::Invalid);
+    }
+    let attempts = history
+        .iter()
+        .filter(|attempt| attempt.day == day && attempt.part == part);
+    let value = i128::from_str(answer).ok();
+    for attempt in attempts {
+        if attempt.verdict == Verdict::Correct {
+            return Err(Refusal::AlreadySolved {
+                answer: attempt.answer.clone(),
+            });
+        }
+        // A bound from an earlier attempt rules out everything beyond it.
+        let known = i128::from_str(&attempt.answer).ok();
+        let ruled_out = match (attempt.verdict, value, known) {
+            (Verdict::TooHigh, Some(value), Some(known)) => value >= known,
+            (Verdict::TooLow, Some(value), Some(known)) => value <= known,
+            (verdict, _, _) => verdict.is_wrong() && attempt.answer == answer,
+        };
+        if ruled_out {
+            return Err(Refusal::KnownWrong {
+                verdict: attempt.verdict,
+            });
+        }
+    }
+    if let Some(last) = history.last() {
+        let interval = match last.verdict {
+            Verdict::RateLimited { wait } => wait.max(MIN_INTERVAL),
+            _ => MIN_INTERVAL,
+        };
+        let ready = last.timestamp.saturating_add(interval);
+        if now < ready {
+            return Err(Refusal::TooSoon { wait: ready - now });
+        }
+    }
+    Ok(())
+}
+
+pub fn answer_url(day: u8) -> String {
+    format!("{}/day/{}/answer", http::BASE_URL, day)
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Submits `answer` unless the history rules it out, then records the verdict.
+pub fn submit<H: Http + ?Sized>(
+    http: &H,
+    session: Option<&str>,
+    history_path: &Path,
+    day: u8,
+    part: Part,
+    answer: &str,
+    now: u64,
+) -> Result<Verdict, SubmitError> {
+    let history = load_history(history_path)?;
+    check(&history, day, part, answer, now).map_err(SubmitError::Refused)?;
+    let session = session.ok_or(SubmitError::NoSession)?;
+    let level = part.number().to_string();
+    let page = http.post_form(
+        &answer_url(day),
+        session,
+        &[("level", &level), ("answer", answer)],
+    )?;
+    let verdict = parse_verdict(&page);
+    let attempt = Attempt {
+        timestamp: now,
+        day,
+        part,
+        answer: answer.to_owned(),
+        verdict,
+    };
+    append_history(history_path, &attempt)?;
+    Ok(verdict)
+}
+
+#[test]
+fn test_parse_verdict() {
+    let page = |text: &str| format!("<main><article><p>{}</p></article></main>", text);
+    assert_eq!(
+        Verdict::Correct,
+        parse_verdict(&page(
+            "That's the right answer! You are one gold star closer."
+        ))
+    );
+    assert_eq!(
+        Verdict::TooLow,
+        parse_verdict(&page(
+            "That's not the right answer; your answer is too low."
+        ))
+    );
+    assert_eq!(
+        Verdict::RateLimited { wait: 252 },
+        parse_verdict(&page(
+            "You gave an answer too recently. You have 4m 12s left to wait."
+        ))
+    );
+    assert_eq!(
+        Verdict::RateLimited { wait: MIN_INTERVAL },
+        parse_verdict(&page(
+            "You gave an answer too recently. You have 5124095576030432h left to wait."
+        ))
+    );
+    assert_eq!(Verdict::Unknown, parse_verdict("<html></html>"));
+    for verdict in [Verdict::TooHigh, Verdict::RateLimited { wait: 30 }] {
+        assert_eq!(Ok(verdict), Verdict::from_str(&verdict.to_string()));
+    }
+}
+
+#[test]
+fn test_check() {
+    let attempt = |timestamp, answer: &str, verdict| Attempt {
+        timestamp,
+        day: 1,
+        part: Part::One,
+        answer: answer.to_owned(),
+        verdict,
+    };
+    let history = vec![
+        attempt(1000, "500", Verdict::TooHigh),
+        attempt(1100, "100", Verdict::TooLow),
+        attempt(1200, "abc", Verdict::Wrong),
+    ];
+    let known_wrong = |verdict| Err(Refusal::KnownWrong { verdict });
+    assert_eq!(
+        known_wrong(Verdict::TooHigh),
+        check(&history, 1, Part::One, "600", 2000)
+    );
+    assert_eq!(
+        known_wrong(Verdict::TooLow),
+        check(&history, 1, Part::One, "100", 2000)
+    );
+    assert_eq!(
+        known_wrong(Verdict::Wrong),
+        check(&history, 1, Part::One, "abc", 2000)
+    );
+    assert_eq!(Ok(()), check(&history, 1, Part::One, "300", 2000));
+    assert_eq!(Ok(()), check(&history, 1, Part::Two, "600", 2000));
+    assert_eq!(
+        Err(Refusal::TooSoon { wait: 10 }),
+        check(&history, 1, Part::One, "300", 1250)
+    );
+    assert_eq!(
+        Err(Refusal::Invalid),
+        check(&history, 1, Part::One, "", 2000)
+    );
+
+    let mut history = history;
+    history.push(attempt(u64::MAX, "200", Verdict::Wrong));
+    assert_eq!(
+        Err(Refusal::TooSoon {
+            wait: u64::MAX - 2000
+        }),
+        check(&history, 1, Part::One, "300", 2000)
+    );
+    history.push(attempt(
+        1250,
+        "200",
+        Verdict::RateLimited { wait: u64::MAX },
+    ));
+    assert_eq!(
+        Err(Refusal::TooSoon {
+            wait: u64::MAX - 2000
+        }),
+        check(&history, 1, Part::One, "300", 2000)
+    );
+    history.push(attempt(1300, "250", Verdict::Correct));
+    assert!(matches!(
+        check(&history, 1, Part::One, "300", 2000),
+        Err(Refusal::AlreadySolved { .. })
+    ));
+}
+
+#[test]
+fn test_submit() {
+    use crate::http::FakeHttp;
+
+    let dir = std::env::temp_dir().join(format!("aoc22-submit-{}", std::process::id()));
+    let path = dir.join(HISTORY_FILE);
+    let http = FakeHttp::new(Ok("That's not the right answer."));
+
+    let verdict = submit(&http, Some("abc"), &path, 2, Part::Two, "42", 1000).unwrap();
+    assert_eq!(Verdict::Wrong, verdict);
+    assert_eq!(
+        vec!["POST https://adventofcode.com/2022/day/2/answer abc level=2&answer=42"],
+        *http.requests.borrow()
+    );
+    let history = load_history(&path).unwrap();
+    assert_eq!("1000\t2\t2\t42\twrong", history[0].to_string());
+
+    // The same wrong answer is never sent again, and nothing is recorded.
+    let err = submit(&http, Some("abc"), &path, 2, Part::Two, "42", 5000).unwrap_err();
+    assert!(matches!(
+        err,
+        SubmitError::Refused(Refusal::KnownWrong { .. })
+    ));
+    assert_eq!(1, http.requests.borrow().len());
+    assert_eq!(1, load_history(&path).unwrap().len());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,123 @@
+use crate::answers::{self, Answers};
+use crate::error::{Error, ParseError};
+use crate::input::InputSource;
+use crate::solution::{Day, Part};
+use std::fmt;
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// No answer is recorded for this part yet.
+    Missing,
+    Error(String),
+}
+
+impl Status {
+    pub fn is_regression(&self) -> bool {
+        matches!(self, Status::Fail { .. } | Status::Error(_))
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            Status::Missing => write!(f, "missing"),
+            Status::Error(msg) => write!(f, "ERROR ({})", msg),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Option<String>,
+    pub status: Status,
+}
+
+/// Solves both parts of `input` and compares them with `answers`.
+pub fn check(day: &Day, input: &str, answers: &Answers) -> Result<Vec<Check>, ParseError> {
+    let parsed = day.parse(input)?;
+    let checks = Part::BOTH
+        .iter()
+        .map(|&part| {
+            let answer = parsed.solve(part);
+            let status = match answers.get(part) {
+                Some(expected) if expected == answer => Status::Pass,
+                Some(expected) => Status::Fail {
+                    expected: expected.to_owned(),
+                },
+                None => Status::Missing,
+            };
+            Check {
+                day: day.number,
+                part,
+                answer: Some(answer),
+                status,
+            }
+        })
+        .collect();
+    Ok(checks)
+}
+
+/// Both parts of `day`, marked as failed with `err`.
+pub fn errors(day: u8, err: &dyn fmt::Display) -> Vec<Check> {
+    Part::BOTH
+        .iter()
+        .map(|&part| Check {
+            day,
+            part,
+            answer: None,
+            status: Status::Error(err.to_string()),
+        })
+        .collect()
+}
+
+fn check_day(day: &Day, inputs: &InputSource, answers_dir: &Path) -> Result<Vec<Check>, Error> {
+    let answers = answers::load(answers_dir, day.number)?;
+    let input = inputs.load(day.number)?;
+    Ok(check(day, &input, &answers)?)
+}
+
+/// Solves both parts of `day` and compares them with the recorded answers.
+pub fn verify_day(day: &Day, inputs: &InputSource, answers_dir: &Path) -> Vec<Check> {
+    check_day(day, inputs, answers_dir).unwrap_or_else(|err| errors(day.number, &err))
+}
+
+#[test]
+fn test_verify_day() {
+    use crate::registry;
+    use std::fs;
+
+    let dir = std::env::temp_dir().join(format!("aoc22-verify-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("day6.txt"), "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n").unwrap();
+    fs::write(dir.join("day6.toml"), "part1 = 8\n").unwrap();
+
+    let day = registry::find(6).unwrap();
+    let inputs = InputSource::Dir(dir.clone());
+    let checks = verify_day(&day, &inputs, &dir);
+    assert_eq!(
+        Status::Fail {
+            expected: "8".to_owned()
+        },
+        checks[0].status
+    );
+    assert_eq!(Some("7"), checks[0].answer.as_deref());
+    assert_eq!(Status::Missing, checks[1].status);
+
+    fs::write(dir.join("day6.toml"), "part1 = 7\npart2 = 19\n").unwrap();
+    let checks = verify_day(&day, &inputs, &dir);
+    assert!(checks.iter().all(|check| check.status == Status::Pass));
+
+    let checks = verify_day(&registry::find(5).unwrap(), &inputs, &dir);
+    assert!(checks.iter().all(|check| check.status.is_regression()));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,348 @@
+use crate::answers;
+use crate::bench::format_duration;
+use crate::examples::{self, Example};
+use crate::input::{self, InputSource};
+use crate::runner;
+use crate::solution::{Day, Part};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The files whose changes trigger a new round for `day`.
+pub fn watched_paths(root: &Path, day: u8, inputs_dir: &Path) -> Vec<PathBuf> {
+    vec![
+        root.join("src").join(format!("day{}.rs", day)),
+        input::day_file(inputs_dir, day),
+    ]
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Polls modification times. A file appearing or disappearing counts as a
+/// change too.
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    stamps: Vec<Option<SystemTime>>,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let stamps = paths.iter().map(|path| modified(path)).collect();
+        Watcher { paths, stamps }
+    }
+
+    /// The files that changed since the previous call.
+    pub fn changed(&mut self) -> Vec<&Path> {
+        let mut changed = Vec::new();
+        for (path, stamp) in self.paths.iter().zip(&mut self.stamps) {
+            let current = modified(path);
+            if current != *stamp {
+                *stamp = current;
+                changed.push(path.as_path());
+            }
+        }
+        changed
+    }
+}
+
+/// One part solved for one input in a round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// The example's name, or `input` for the real input.
+    pub source: String,
+    pub part: Part,
+    /// The answer, or why there is none.
+    pub answer: Result<String, String>,
+    pub elapsed: Option<Duration>,
+    /// The recorded answer, for examples that have one.
+    pub expected: Option<String>,
+}
+
+fn entries(
+    source: &str,
+    day: &Day,
+    inputs: &InputSource,
+    expected: &answers::Answers,
+) -> Vec<Entry> {
+    let run = runner::run_day(day, inputs, &Part::BOTH);
+    Part::BOTH
+        .iter()
+        .enumerate()
+        .map(|(i, &part)| {
+            let (answer, elapsed) = match &run.result {
+                Ok(part_runs) => (Ok(part_runs[i].answer.clone()), Some(part_runs[i].elapsed)),
+                Err(err) => (Err(err.to_string()), None),
+            };
+            Entry {
+                source: source.to_owned(),
+                part,
+                answer,
+                elapsed,
+                expected: expected.get(part).map(str::to_owned),
+            }
+        })
+        .collect()
+}
+
+/// Solves both parts of every example of `day` and of its real input.
+pub fn run_round(day: &Day, examples: &[Example], inputs: &InputSource) -> Vec<Entry> {
+    let mut round = Vec::new();
+    for example in examples.iter().filter(|example| example.day == day.number) {
+        let expected = match answers::load_file(example.day, example.answers.clone()) {
+            Ok(expected) => expected,
+            Err(err) => {
+                crate::info!("{}", err);
+                answers::Answers::default()
+            }
+        };
+        let source = InputSource::File(example.input.clone());
+        round.extend(entries(&example.name, day, &source, &expected));
+    }
+    round.extend(entries("input", day, inputs, &answers::Answers::default()));
+    round
+}
+
+/// Where `watch --once` keeps the previous round of `day` between runs.
+pub fn state_file(target_dir: &Path, day: u8) -> PathBuf {
+    target_dir.join("watch").join(format!("day{}.tsv", day))
+}
+
+fn clean(text: &str) -> String {
+    text.replace(['\t', '\n', '\r'], " ")
+}
+
+/// Saves a round as one `source part ok|error answer elapsed_ns` line per
+/// entry. Expected answers are not kept; they are re-read every round.
+pub fn save_round(path: &Path, round: &[Entry]) -> io::Result<()> {
+    let mut text = String::new();
+    for entry in round {
+        let (status, answer) = match &entry.answer {
+            Ok(answer) => ("ok", answer),
+            Err(err) => ("error", err),
+        };
+        let elapsed = entry
+            .elapsed
+            .map_or("-".to_owned(), |elapsed| elapsed.as_nanos().to_string());
+        let _ = writeln!(
+            text,
+            "{}\t{}\t{}\t{}\t{}",
+            clean(&entry.source),
+            entry.part.number(),
+            status,
+            clean(answer),
+            elapsed
+        );
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, text)
+}
+
+fn parse_entry(line: &str) -> Option<Entry> {
+    let mut fields = line.split('\t');
+    let source = fields.next()?.to_owned();
+    let part = match fields.next()? {
+        "1" => Part::One,
+        "2" => Part::Two,
+        _ => return None,
+    };
+    let answer = match (fields.next()?, fields.next()?.to_owned()) {
+        ("ok", answer) => Ok(answer),
+        ("error", err) => Err(err),
+        _ => return None,
+    };
+    let elapsed = match fields.next()? {
+        "-" => None,
+        nanos => Some(Duration::from_nanos(u64::from_str(nanos).ok()?)),
+    };
+    Some(Entry {
+        source,
+        part,
+        answer,
+        elapsed,
+        expected: None,
+    })
+}
+
+/// The previous round saved at `path`; nothing if there is none yet or it
+/// cannot be read.
+pub fn load_round(path: &Path) -> Vec<Entry> {
+    let text = fs::read_to_string(path).unwrap_or_default();
+    text.lines().filter_map(parse_entry).collect()
+}
+
+fn signed_duration(current: Duration, previous: Duration) -> String {
+    if current >= previous {
+        format!("+{}", format_duration(current - previous))
+    } else {
+        format!("-{}", format_duration(previous - current))
+    }
+}
+
+/// One line per entry: the answer, whether it matches the example's
+/// answer, what it was in the previous round if it changed, and the time
+/// with its difference from the previous round.
+pub fn report(round: &[Entry], previous: &[Entry]) -> String {
+    let width = round
+        .iter()
+        .map(|entry| entry.source.len())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for entry in round {
+        let before = previous
+            .iter()
+            .find(|before| before.source == entry.source && before.part == entry.part);
+        let _ = write!(
+            out,
+            "{:<width$} part{}: ",
+            entry.source,
+            entry.part.number(),
+            width = width
+        );
+        match &entry.answer {
+            Ok(answer) => {
+                let _ = write!(out, "{}", answer);
+                match &entry.expected {
+                    Some(expected) if expected == answer => out.push_str(" pass"),
+                    Some(expected) => {
+                        let _ = write!(out, " FAIL (expected {})", expected);
+                    }
+                    None => {}
+                }
+            }
+            Err(err) => {
+                let _ = write!(out, "ERROR ({})", err);
+            }
+        }
+        if let Some(before) = before.filter(|before| before.answer != entry.answer) {
+            match &before.answer {
+                Ok(answer) => {
+                    let _ = write!(out, " [was {}]", answer);
+                }
+                Err(_) => out.push_str(" [was an error]"),
+            }
+        }
+        if let Some(elapsed) = entry.elapsed {
+            let _ = write!(out, "  {}", format_duration(elapsed));
+            if let Some(previous) = before.and_then(|before| before.elapsed) {
+                let _ = write!(out, " ({})", signed_duration(elapsed, previous));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Runs one round, prints it against the round saved at `state`, and saves
+/// it in turn.
+pub fn run_once(day: &Day, inputs: &InputSource, state: &Path) -> io::Result<String> {
+    let examples = examples::discover(Path::new(examples::DEFAULT_DIR)).unwrap_or_default();
+    let round = run_round(day, &examples, inputs);
+    let report = report(&round, &load_round(state));
+    save_round(state, &round)?;
+    Ok(report)
+}
+
+#[test]
+fn test_watcher() {
+    let dir = std::env::temp_dir().join(format!("aoc22-watch-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("day9.txt");
+    let mut watcher = Watcher::new(vec![file.clone()]);
+    assert!(watcher.changed().is_empty());
+
+    fs::write(&file, "R 4\n").unwrap();
+    assert_eq!(vec![file.as_path()], watcher.changed());
+    assert!(watcher.changed().is_empty());
+
+    let later = modified(&file).unwrap() + Duration::from_secs(5);
+    fs::File::options()
+        .write(true)
+        .open(&file)
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+    assert_eq!(vec![file.as_path()], watcher.changed());
+
+    let state = state_file(&dir, 9);
+    assert!(load_round(&state).is_empty());
+    let round = vec![
+        Entry {
+            source: "day9".to_owned(),
+            part: Part::One,
+            answer: Ok("13".to_owned()),
+            elapsed: Some(Duration::from_micros(3)),
+            expected: Some("13".to_owned()),
+        },
+        Entry {
+            source: "input".to_owned(),
+            part: Part::Two,
+            answer: Err("bad\tinput".to_owned()),
+            elapsed: None,
+            expected: None,
+        },
+    ];
+    save_round(&state, &round).unwrap();
+    let loaded = load_round(&state);
+    assert_eq!(Ok("13".to_owned()), loaded[0].answer);
+    assert_eq!(Some(Duration::from_micros(3)), loaded[0].elapsed);
+    assert_eq!(Err("bad input".to_owned()), loaded[1].answer);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_report() {
+    let entry = |source: &str, answer: Result<&str, &str>, micros: Option<u64>| Entry {
+        source: source.to_owned(),
+        part: Part::One,
+        answer: answer.map(str::to_owned).map_err(str::to_owned),
+        elapsed: micros.map(Duration::from_micros),
+        expected: None,
+    };
+    let previous = vec![
+        entry("day9", Ok("13"), Some(3)),
+        entry("input", Ok("6366"), Some(2_500)),
+    ];
+    let mut round = vec![
+        entry("day9", Ok("13"), Some(2)),
+        entry("input", Ok("6367"), Some(2_750)),
+    ];
+    round[0].expected = Some("13".to_owned());
+    assert_eq!(
+        "day9  part1: 13 pass  2.0µs (-1.0µs)\n\
+         input part1: 6367 [was 6366]  2.75ms (+250.0µs)\n",
+        report(&round, &previous)
+    );
+
+    let round = vec![entry("input", Err("line 3: bad"), None)];
+    assert_eq!(
+        "input part1: ERROR (line 3: bad) [was 6366]\n",
+        report(&round, &previous)
+    );
+    assert_eq!("input part1: ERROR (line 3: bad)\n", report(&round, &[]));
+}
+
+#[test]
+fn test_run_round() {
+    let day = crate::registry::find(6).unwrap();
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(examples::DEFAULT_DIR);
+    let examples = examples::discover(&dir).unwrap();
+    let round = run_round(&day, &examples, &InputSource::File(dir.join("day6.txt")));
+    // Five examples and the input, two parts each.
+    assert_eq!(12, round.len());
+    assert!(round[..10]
+        .iter()
+        .all(|entry| entry.expected.is_some()
+            && entry.answer.as_ref().ok() == entry.expected.as_ref()));
+    assert_eq!("input", round[11].source);
+}